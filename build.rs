@@ -1,3 +1,106 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Uncompressed secp256k1 public key verifying the token metadata records of
+// release builds, unless overridden by the TOKEN_SIGNER_PUBKEY environment
+// variable.
+const PRODUCTION_TOKEN_SIGNER_PUBKEY: &str = "04324916c365131561ef2ce73b6e3f384c40e23f1cb7e1448822e704371ccbdef8ac5dd88f1589d5a1f94ac7eaf5140517d05aadb3e164cf67a031953f916c86b6";
+
+// Uncompressed secp256k1 public key verifying the token metadata records of
+// the test suite, only used with the `test-signer` feature. Its private key
+// is in `tests/utils.py`.
+const TEST_TOKEN_SIGNER_PUBKEY: &str = "047a315272e69076bf0bfdeeba03666272af8841302433902f489c220896c1df697ede5cb727ba8378c6d0629b8b4d541b96a25510514d56d4e1d6f41548d322ed";
+
+// List of the tokens compiled into the app.
+const TOKEN_LIST: &str = "tokens.csv";
+
+// Same limit as the tickers of PROVIDE_TOKEN_INFO records.
+const MAX_TICKER_LEN: usize = 12;
+
 fn main() {
     println!("cargo:rerun-if-changed=script.ld");
+    println!("cargo:rerun-if-env-changed=TOKEN_SIGNER_PUBKEY");
+    println!("cargo:rerun-if-changed={}", TOKEN_LIST);
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(
+        Path::new(&out_dir).join("token_signer.rs"),
+        token_signer_pubkey(),
+    )
+    .unwrap();
+    fs::write(Path::new(&out_dir).join("tokens.rs"), token_registry()).unwrap();
+}
+
+fn token_signer_pubkey() -> String {
+    // Forged records would be trusted with the test key, whose private key is
+    // public, so it must be explicitly opted into
+    let pubkey = match env::var("TOKEN_SIGNER_PUBKEY") {
+        Ok(pubkey) => pubkey,
+        Err(_) if env::var_os("CARGO_FEATURE_TEST_SIGNER").is_some() => {
+            println!("cargo:warning=using the test token signer key");
+            TEST_TOKEN_SIGNER_PUBKEY.into()
+        }
+        Err(_) => PRODUCTION_TOKEN_SIGNER_PUBKEY.into(),
+    };
+    let pubkey = hex::decode(pubkey.trim()).expect("TOKEN_SIGNER_PUBKEY is not valid hex");
+    if pubkey.len() != 65 || pubkey[0] != 0x04 {
+        panic!("TOKEN_SIGNER_PUBKEY must be an uncompressed secp256k1 public key");
+    }
+    format!("pub const TOKEN_SIGNER_PUBKEY: [u8; 65] = {:?};\n", pubkey)
+}
+
+// Generates the table of the tokens of the token list, sorted by chain id and
+// address to be searched with a binary search.
+fn token_registry() -> String {
+    let list = fs::read_to_string(TOKEN_LIST).expect("cannot read the token list");
+
+    let mut tokens = Vec::new();
+    for (i, line) in list.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let error = |msg: &str| -> ! { panic!("{}:{}: {}", TOKEN_LIST, i + 1, msg) };
+
+        let columns: Vec<&str> = line.split(',').map(str::trim).collect();
+        let [chain_id, address, ticker, decimals] = columns[..] else {
+            error("expected chain_id,address,ticker,decimals");
+        };
+        let chain_id: u32 = chain_id
+            .parse()
+            .unwrap_or_else(|_| error("invalid chain id"));
+        let address = hex::decode(address.trim_start_matches("0x"))
+            .ok()
+            .filter(|address| address.len() == 20)
+            .unwrap_or_else(|| error("invalid address"));
+        if ticker.is_empty()
+            || ticker.len() > MAX_TICKER_LEN
+            || !ticker.bytes().all(|c| c.is_ascii_graphic())
+        {
+            error("invalid ticker");
+        }
+        let decimals: u8 = decimals
+            .parse()
+            .unwrap_or_else(|_| error("invalid decimals"));
+        tokens.push((chain_id, address, ticker.to_string(), decimals));
+    }
+
+    tokens.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    if let Some(w) = tokens
+        .windows(2)
+        .find(|w| (w[0].0, &w[0].1) == (w[1].0, &w[1].1))
+    {
+        panic!("{}: duplicate token {}", TOKEN_LIST, w[0].2);
+    }
+
+    let mut out = String::from("const KNOWN_TOKENS: &[KnownToken] = &[\n");
+    for (chain_id, address, ticker, decimals) in tokens {
+        out += &format!(
+            "    KnownToken {{ chain_id: {}, address: {:?}, ticker: {:?}, decimals: {} }},\n",
+            chain_id, address, ticker, decimals
+        );
+    }
+    out += "];\n";
+    out
 }
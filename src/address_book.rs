@@ -0,0 +1,145 @@
+use crate::cfx_addr::Network;
+use crate::consts::{ADDRRESS_BYTES_LEN, MAX_CONTACTS};
+use crate::types::DisplayValue;
+use crate::AppSW;
+use alloc::{string::String, vec::Vec};
+use ledger_device_sdk::nvm::*;
+use ledger_device_sdk::NVMData;
+
+/// Maximum length of the label of a contact.
+pub const MAX_LABEL_LEN: usize = 20;
+
+/// Recipient of an approved transaction, which may be saved as a contact.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Recipient {
+    pub address: [u8; ADDRRESS_BYTES_LEN],
+    pub chain_id: u64,
+    /// Whether the address is displayed in hex, as an eSpace address.
+    pub espace: bool,
+}
+
+impl Recipient {
+    /// Formats the address in base32 for its chain, or in hex for eSpace.
+    pub fn address_str(&self) -> Result<String, AppSW> {
+        let network = Network::from_network_id(self.chain_id);
+        DisplayValue::Address(self.address).format(network, self.espace)
+    }
+}
+
+/// Recipient saved with a label, free slots have an empty label.
+#[derive(Clone, Copy)]
+pub struct Contact {
+    pub recipient: Recipient,
+    label_len: u8,
+    label: [u8; MAX_LABEL_LEN],
+}
+
+impl Contact {
+    const EMPTY: Contact = Contact {
+        recipient: Recipient {
+            address: [0u8; ADDRRESS_BYTES_LEN],
+            chain_id: 0,
+            espace: false,
+        },
+        label_len: 0,
+        label: [0u8; MAX_LABEL_LEN],
+    };
+
+    pub fn label(&self) -> &str {
+        // Labels are checked by parse_label before being saved
+        core::str::from_utf8(&self.label[..self.label_len as usize]).unwrap_or_default()
+    }
+
+    fn is_free(&self) -> bool {
+        self.label_len == 0
+    }
+}
+
+/// Reads a contact label, made of 1 to [`MAX_LABEL_LEN`] printable ASCII
+/// characters or spaces, as it is displayed as is.
+pub fn parse_label(data: &[u8]) -> Result<&str, AppSW> {
+    if data.is_empty()
+        || data.len() > MAX_LABEL_LEN
+        || !data.iter().all(|c| *c == b' ' || c.is_ascii_graphic())
+    {
+        return Err(AppSW::InvalidData);
+    }
+    core::str::from_utf8(data).map_err(|_| AppSW::InvalidData)
+}
+
+// This is necessary to store the object in NVM and not in RAM
+#[link_section = ".nvm_data"]
+static mut DATA: NVMData<AtomicStorage<[Contact; MAX_CONTACTS]>> =
+    NVMData::new(AtomicStorage::new(&[Contact::EMPTY; MAX_CONTACTS]));
+
+/// Labels of the recipients saved by the user, kept across app restarts.
+#[derive(Clone, Copy, Default)]
+pub struct AddressBook;
+
+impl AddressBook {
+    fn storage(&self) -> &[Contact; MAX_CONTACTS] {
+        let data = &raw const DATA;
+        unsafe { (*data).get_ref() }.get_ref()
+    }
+
+    fn update(&self, contacts: &[Contact; MAX_CONTACTS]) {
+        let data = &raw mut DATA;
+        unsafe { (*data).get_mut() }.update(contacts);
+    }
+
+    /// Returns the saved contacts, in the order they were saved.
+    pub fn contacts(&self) -> Vec<Contact> {
+        self.storage()
+            .iter()
+            .filter(|c| !c.is_free())
+            .copied()
+            .collect()
+    }
+
+    /// Returns the label of `address` on `chain_id`, if it was saved.
+    pub fn label(&self, address: &[u8; ADDRRESS_BYTES_LEN], chain_id: u64) -> Option<&str> {
+        self.storage()
+            .iter()
+            .find(|c| {
+                !c.is_free() && &c.recipient.address == address && c.recipient.chain_id == chain_id
+            })
+            .map(|c| c.label())
+    }
+
+    /// Saves `recipient` with a label read by [`parse_label`], replacing its
+    /// previous label if any.
+    pub fn save(&self, recipient: Recipient, label: &str) -> Result<(), AppSW> {
+        let mut contacts = *self.storage();
+        let slot = contacts
+            .iter()
+            .position(|c| !c.is_free() && c.recipient == recipient)
+            .or_else(|| contacts.iter().position(|c| c.is_free()))
+            .ok_or(AppSW::AddressBookFull)?;
+        let mut contact = Contact {
+            recipient,
+            label_len: label.len() as u8,
+            label: [0u8; MAX_LABEL_LEN],
+        };
+        contact.label[..label.len()].copy_from_slice(label.as_bytes());
+        contacts[slot] = contact;
+        self.update(&contacts);
+        Ok(())
+    }
+
+    /// Removes the contact at `index` in [`AddressBook::contacts`].
+    pub fn remove(&self, index: usize) -> Result<(), AppSW> {
+        let mut contacts = *self.storage();
+        let slot = contacts
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_free())
+            .nth(index)
+            .map(|(slot, _)| slot)
+            .ok_or(AppSW::InternalError)?;
+        // Later contacts are moved up to keep the order they were saved in
+        contacts.copy_within(slot + 1.., slot);
+        contacts[MAX_CONTACTS - 1] = Contact::EMPTY;
+        self.update(&contacts);
+        Ok(())
+    }
+}
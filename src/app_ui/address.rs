@@ -17,31 +17,144 @@
 
 use crate::cfx_addr::{cfx_addr_encode, Network};
 use crate::consts::ADDRRESS_BYTES_LEN;
+use crate::handlers::sign_tx::TxContext;
+use crate::utils::eip55_encode;
 use crate::AppSW;
 
 #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
 use ledger_device_sdk::ui::{
     bitmaps::{CROSSMARK, EYE, VALIDATE_14},
-    gadgets::{Field, MultiFieldReview},
+    gadgets::{clear_screen, Field, MultiFieldReview, Page},
 };
 
 #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
-use ledger_device_sdk::nbgl::{NbglAddressReview, NbglGlyph};
+use ledger_device_sdk::nbgl::{Field, NbglAddressReview, NbglGlyph, NbglStatus};
+
+use alloc::{string::String, vec::Vec};
 
 #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
 use ledger_device_sdk::include_gif;
 
-pub fn ui_display_pk(addr: &[u8], chain_id: u32) -> Result<bool, AppSW> {
+/// Displays a Core address in base32, and the eSpace address it is mapped
+/// to when `mapped` is given.
+pub fn ui_display_pk(addr: &[u8], chain_id: u32, mapped: Option<&[u8]>) -> Result<bool, AppSW> {
     let addr = &addr[addr.len() - ADDRRESS_BYTES_LEN..]; // last 20 bytes
     let network = Network::from_network_id(chain_id as u64);
     let cfx_addr = cfx_addr_encode(addr, network).map_err(|_e| AppSW::AddrDisplayFail)?;
 
+    let mapped_str = match mapped {
+        Some(mapped) => eip55_encode(mapped)?,
+        None => String::new(),
+    };
+    let mut extra_fields = Vec::new();
+    if mapped.is_some() {
+        extra_fields.push(Field {
+            name: "Mapped eSpace address",
+            value: mapped_str.as_str(),
+        });
+    }
+
+    review_address(&cfx_addr, "Verify CFX address", &extra_fields)
+}
+
+/// Displays an eSpace address as an EIP-55 checksummed hex string.
+pub fn ui_display_espace_pk(addr: &[u8]) -> Result<bool, AppSW> {
+    let addr = &addr[addr.len() - ADDRRESS_BYTES_LEN..]; // last 20 bytes
+    let hex_addr = eip55_encode(addr)?;
+
+    review_address(&hex_addr, "Verify eSpace address", &[])
+}
+
+/// Displays a host supplied address found to belong to the device, and
+/// returns true if user confirmed it.
+pub fn ui_display_verified_address(addr_str: &str) -> Result<bool, AppSW> {
+    let confirmed = review_address(addr_str, "Verify CFX address", &[])?;
+
+    // NBGL devices show the verified status once the command returns
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    if confirmed {
+        let verified = Page::from((["Address", "verified"], &VALIDATE_14));
+        clear_screen();
+        verified.place_and_wait();
+    }
+
+    Ok(confirmed)
+}
+
+/// Displays an error for a host supplied address not belonging to the device.
+#[allow(unused_variables)]
+pub fn ui_display_address_mismatch(ctx: &mut TxContext) {
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    {
+        let mismatch = Page::from((["Address does not", "match this device"], &CROSSMARK));
+        clear_screen();
+        mismatch.place_and_wait();
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+    {
+        NbglStatus::new()
+            .text("Address does not match this device")
+            .show(false);
+        ctx.home.show_and_return();
+    }
+}
+
+/// Displays the recipient of the last approved transaction with the label
+/// the host asks to save it with, and returns true if user confirmed it.
+pub fn ui_display_contact(addr_str: &str, label: &str) -> Result<bool, AppSW> {
+    review_address(
+        addr_str,
+        "Save contact",
+        &[Field {
+            name: "Label",
+            value: label,
+        }],
+    )
+}
+
+/// Displays whether a contact was saved, once reviewed by the user.
+#[allow(unused_variables)]
+pub fn ui_display_contact_saved(result: Result<(), AppSW>, ctx: &mut TxContext) {
+    let text = match result {
+        Ok(()) => ["Contact", "saved"],
+        Err(AppSW::AddressBookFull) => ["Address book", "is full"],
+        Err(_) => ["Contact", "not saved"],
+    };
+
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    {
+        let icon = if result.is_ok() {
+            &VALIDATE_14
+        } else {
+            &CROSSMARK
+        };
+        let status = Page::from((text, icon));
+        clear_screen();
+        status.place_and_wait();
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+    {
+        NbglStatus::new()
+            .text(&alloc::format!("{} {}", text[0], text[1]))
+            .show(result.is_ok());
+        ctx.home.show_and_return();
+    }
+}
+
+#[allow(unused_variables)]
+fn review_address(addr_str: &str, title: &str, extra_fields: &[Field]) -> Result<bool, AppSW> {
     #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
     {
-        let my_field = [Field {
+        let mut my_field = Vec::from([Field {
             name: "Address",
-            value: cfx_addr.as_str(),
-        }];
+            value: addr_str,
+        }]);
+        my_field.extend(extra_fields.iter().map(|field| Field {
+            name: field.name,
+            value: field.value,
+        }));
 
         let my_review = MultiFieldReview::new(
             &my_field,
@@ -66,7 +179,8 @@ pub fn ui_display_pk(addr: &[u8], chain_id: u32) -> Result<bool, AppSW> {
         // Display the address confirmation screen.
         Ok(NbglAddressReview::new()
             .glyph(&CFX)
-            .review_title("Verify CFX address")
-            .show(&cfx_addr))
+            .review_title(title)
+            .set_tag_value_list(extra_fields)
+            .show(addr_str))
     }
 }
@@ -19,13 +19,17 @@ use ledger_device_sdk::io::Comm;
 
 #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
 use ledger_device_sdk::ui::{
-    bitmaps::{Glyph, BACK, CERTIFICATE, COGGLE, DASHBOARD_X},
-    gadgets::{EventOrPageIndex, MultiPageMenu, Page},
+    bitmaps::{Glyph, BACK, CERTIFICATE, COGGLE, CROSSMARK, DASHBOARD_X, EYE, VALIDATE_14},
+    gadgets::{clear_screen, EventOrPageIndex, Field, MultiFieldReview, MultiPageMenu, Page},
 };
 
+use crate::address_book::AddressBook;
 use crate::settings::Settings;
+use alloc::format;
+#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+use alloc::{string::String, vec::Vec};
 #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
-use ledger_device_sdk::nbgl::{NbglGlyph, NbglHomeAndSettings};
+use ledger_device_sdk::nbgl::{NbglChoice, NbglGlyph, NbglHomeAndSettings, NbglStatus, PageIndex};
 
 #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
 use crate::Instruction;
@@ -58,6 +62,7 @@ fn ui_setting_menu(comm: &mut Comm) -> Event<Instruction> {
     loop {
         let pages = [
             &Page::from((["Blind Signing", bs_status], true)),
+            &Page::from((["Address book", "Manage contacts"], true)),
             &Page::from(("Back", &BACK)),
         ];
         match MultiPageMenu::new(comm, &pages).show() {
@@ -79,7 +84,75 @@ fn ui_setting_menu(comm: &mut Comm) -> Event<Instruction> {
                     }
                 }
             }
-            EventOrPageIndex::Index(1) => return ui_menu_main(comm),
+            EventOrPageIndex::Index(1) => return ui_address_book_menu(comm),
+            EventOrPageIndex::Index(2) => return ui_menu_main(comm),
+            EventOrPageIndex::Index(_) => (),
+        }
+    }
+}
+
+/// Lists the contacts of the address book, each of them can be reviewed and
+/// removed.
+#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+fn ui_address_book_menu(comm: &mut Comm) -> Event<Instruction> {
+    let address_book: AddressBook = Default::default();
+
+    loop {
+        let contacts = address_book.contacts();
+        let addresses: Vec<String> = contacts
+            .iter()
+            .map(|contact| contact.recipient.address_str().unwrap_or_default())
+            .collect();
+        // Addresses are shortened to fit in the menu
+        let short_addresses: Vec<String> = addresses
+            .iter()
+            .map(|addr| match addr.len() > 16 {
+                true => format!("{}...{}", &addr[..10], &addr[addr.len() - 6..]),
+                false => addr.clone(),
+            })
+            .collect();
+
+        let mut pages: Vec<Page> = contacts
+            .iter()
+            .zip(short_addresses.iter())
+            .map(|(contact, addr)| Page::from(([contact.label(), addr.as_str()], true)))
+            .collect();
+        if pages.is_empty() {
+            pages.push(Page::from((["No contacts", "saved"], true)));
+        }
+        let back = pages.len();
+        pages.push(Page::from(("Back", &BACK)));
+        let page_refs: Vec<&Page> = pages.iter().collect();
+
+        match MultiPageMenu::new(comm, &page_refs).show() {
+            EventOrPageIndex::Event(e) => return e,
+            EventOrPageIndex::Index(i) if i == back => return ui_setting_menu(comm),
+            EventOrPageIndex::Index(i) if i < contacts.len() => {
+                let fields = [
+                    Field {
+                        name: "Label",
+                        value: contacts[i].label(),
+                    },
+                    Field {
+                        name: "Address",
+                        value: addresses[i].as_str(),
+                    },
+                ];
+                let review = MultiFieldReview::new(
+                    &fields,
+                    &["Review contact"],
+                    Some(&EYE),
+                    "Remove",
+                    Some(&CROSSMARK),
+                    "Keep",
+                    Some(&VALIDATE_14),
+                );
+                if review.show() && address_book.remove(i).is_err() {
+                    let status = Page::from((["Contact", "not removed"], &CROSSMARK));
+                    clear_screen();
+                    status.place_and_wait();
+                }
+            }
             EventOrPageIndex::Index(_) => (),
         }
     }
@@ -108,6 +181,11 @@ pub fn ui_menu_main(comm: &mut Comm) -> Event<Instruction> {
     }
 }
 
+/// Index of the settings switch opening the address book, see
+/// [`ui_address_book_settings`].
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+const ADDRESS_BOOK_SETTING: usize = 2;
+
 #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
 pub fn ui_menu_main(_: &mut Comm) -> NbglHomeAndSettings {
     // Load glyph from 64x64 4bpp gif file with include_gif macro. Creates an NBGL compatible glyph.
@@ -119,6 +197,7 @@ pub fn ui_menu_main(_: &mut Comm) -> NbglHomeAndSettings {
     let settings_strings = [
         ["Blind Signing", "Enable transaction blind signing."],
         ["Display Data", "Allow display of transaction data."],
+        ["Address book", "Review and remove saved contacts."],
     ];
     let mut settings: Settings = Default::default();
 
@@ -132,3 +211,45 @@ pub fn ui_menu_main(_: &mut Comm) -> NbglHomeAndSettings {
         )
         .settings(settings.get_mut(), &settings_strings)
 }
+
+/// Lists the contacts of the address book one by one, each of them can be
+/// removed, once the address book switch was turned on in the settings.
+///
+/// The SDK settings only support switches, so this is checked after each
+/// touch of the home and settings screen, the switch being turned back off.
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+pub fn ui_address_book_settings(home: &mut NbglHomeAndSettings) {
+    let settings: Settings = Default::default();
+    if settings.get_element(ADDRESS_BOOK_SETTING).unwrap_or(0) == 0
+        || settings.set_element(ADDRESS_BOOK_SETTING, 0).is_err()
+    {
+        return;
+    }
+
+    let address_book: AddressBook = Default::default();
+    let contacts = address_book.contacts();
+    if contacts.is_empty() {
+        NbglStatus::new().text("No contacts saved").show(false);
+    }
+    // Later contacts move up as contacts are removed
+    let mut removed = 0;
+    for (i, contact) in contacts.iter().enumerate() {
+        let title = format!("{} ({} of {})", contact.label(), i + 1, contacts.len());
+        let address = contact.recipient.address_str().unwrap_or_default();
+        if NbglChoice::new().show(&title, &address, "Remove contact", "Keep contact") {
+            let result = address_book.remove(i - removed);
+            let text = match result {
+                Ok(()) => "Contact removed",
+                Err(_) => "Contact not removed",
+            };
+            NbglStatus::new().text(text).show(result.is_ok());
+            if result.is_ok() {
+                removed += 1;
+            }
+        }
+    }
+
+    home.set_start_page(PageIndex::Settings(0));
+    home.show_and_return();
+    home.set_start_page(PageIndex::Home);
+}
@@ -0,0 +1,88 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::AppSW;
+use alloc::{format, string::String};
+
+#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+use ledger_device_sdk::ui::{
+    bitmaps::{CROSSMARK, EYE, VALIDATE_14},
+    gadgets::{Field, MultiFieldReview},
+};
+
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+use ledger_device_sdk::include_gif;
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+use ledger_device_sdk::nbgl::{Field, NbglGlyph, NbglReview, TransactionType};
+
+/// Displays a personal message and returns true if user approved it.
+///
+/// The message is shown as text when it is printable UTF-8, and as
+/// hexadecimal otherwise.
+///
+/// # Arguments
+///
+/// * `message` - Raw message to be displayed for validation
+pub fn ui_display_personal_msg(message: &[u8]) -> Result<bool, AppSW> {
+    let message_str = message_display_str(message);
+
+    let my_fields = [Field {
+        name: "Message",
+        value: message_str.as_str(),
+    }];
+
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    {
+        let my_review = MultiFieldReview::new(
+            &my_fields,
+            &["Review ", "Message"],
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+        Ok(my_review.show())
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+    {
+        // Load glyph from 64x64 4bpp gif file with include_gif macro. Creates an NBGL compatible glyph.
+        #[cfg(any(target_os = "stax", target_os = "flex"))]
+        const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_64.gif", NBGL));
+        #[cfg(target_os = "apex_p")]
+        const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_48.png", NBGL));
+
+        let review: NbglReview = NbglReview::new()
+            .tx_type(TransactionType::Message)
+            .titles("Review message", "", "Sign message")
+            .glyph(&CFX);
+
+        Ok(review.show(&my_fields))
+    }
+}
+
+// Printable ASCII messages are displayed as is, anything else as hex, as
+// device fonts only render ASCII.
+fn message_display_str(message: &[u8]) -> String {
+    let printable = message
+        .iter()
+        .all(|c| *c == b'\n' || *c == b' ' || c.is_ascii_graphic());
+    match !message.is_empty() && printable {
+        true => message.iter().map(|c| *c as char).collect(),
+        false => format!("0x{}", hex::encode(message).to_uppercase()),
+    }
+}
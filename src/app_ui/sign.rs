@@ -14,10 +14,12 @@
  *  See the License for the specific language governing permissions and
  *  limitations under the License.
  *****************************************************************************/
-use crate::cfx_addr::{cfx_addr_encode, Network};
+use crate::address_book::AddressBook;
+use crate::cfx_addr::Network;
+use crate::contracts::DecodedCall;
 use crate::handlers::sign_tx::TxContext;
 use crate::settings::Settings;
-use crate::types::{Transaction, U256};
+use crate::types::{DisplayValue, Transaction, U256};
 use crate::AppSW;
 
 #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
@@ -31,7 +33,7 @@ use ledger_device_sdk::include_gif;
 #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
 use ledger_device_sdk::nbgl::{Field, NbglChoice, NbglGlyph, NbglReview, PageIndex};
 
-use alloc::{format, vec};
+use alloc::{format, string::String, vec, vec::Vec};
 
 /// Displays a transaction and returns true if user approved it.
 ///
@@ -40,34 +42,112 @@ use alloc::{format, vec};
 /// # Arguments
 ///
 /// * `tx` - Transaction to be displayed for validation
+/// * `call` - Contract call decoded from the transaction data, reviewed field
+///   by field instead of as raw data
 #[allow(unused_variables)]
-pub fn ui_display_tx(tx: &Transaction, ctx: &mut TxContext) -> Result<bool, AppSW> {
-    let fully_decoded = tx.fully_decoded();
+pub fn ui_display_tx(
+    tx: &Transaction,
+    call: Option<&DecodedCall>,
+    ctx: &mut TxContext,
+) -> Result<bool, AppSW> {
+    let fully_decoded = tx.fully_decoded() || call.is_some_and(|call| !call.blind);
 
     let value_str = tx.value.cfx_str().ok_or(AppSW::TxDisplayFail)?;
     let value_with_unit = format!("{} CFX", value_str);
 
+    // eSpace addresses are displayed in hex, Core ones in base32, along with
+    // their address book label or trusted name if any
     let network = Network::from_network_id(tx.chain_id);
-    let to_str = cfx_addr_encode(&*tx.to, network).map_err(|_e| AppSW::AddrDisplayFail)?;
+    let address_book: AddressBook = Default::default();
+    let format_value = |value: &DisplayValue| -> Result<String, AppSW> {
+        let label = value
+            .chain_address(tx.is_espace())
+            .and_then(|addr| address_book.label(addr, tx.chain_id));
+        match label {
+            Some(label) => Ok(format!(
+                "{} (contact): {}",
+                label,
+                value.format(network, tx.is_espace())?
+            )),
+            None => value.format_named(network, tx.is_espace(), tx.chain_id, &ctx.names),
+        }
+    };
+    let to_str = match &tx.to {
+        Some(to) => format_value(&DisplayValue::Address(to.0))?,
+        None => String::new(),
+    };
+
+    // Contract deployments show the init code instead of a recipient
+    let init_code_size = format!("{} bytes", tx.data_len);
+    let init_code_hash = format!("0x{}", hex::encode(tx.data_hash.0).to_uppercase());
 
     let fee_str = tx.max_gas_fee().cfx_str().ok_or(AppSW::TxDisplayFail)?;
     let fee_with_unit = format!("{} CFX", fee_str);
 
+    let call_values = match call {
+        Some(call) => call
+            .fields
+            .iter()
+            .map(|field| format_value(&field.value))
+            .collect::<Result<Vec<String>, AppSW>>()?,
+        None => Vec::new(),
+    };
+
+    // Nano reviews have no room for the operation in their title
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    let call_label = call.map(|call| call.label());
+
     // Define transaction review fields
-    let mut my_fields = vec![
-        Field {
+    let mut my_fields = vec![];
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    if let Some(label) = &call_label {
+        my_fields.push(Field {
+            name: "Operation",
+            value: label.as_str(),
+        });
+    }
+    if let Some(call) = call {
+        for (field, value) in call.fields.iter().zip(call_values.iter()) {
+            my_fields.push(Field {
+                name: field.name.as_str(),
+                value: value.as_str(),
+            });
+        }
+    }
+    // Contract calls usually transfer no CFX
+    let shows_value = call.is_some_and(|call| call.shows_value);
+    if call.is_none() || (tx.value > U256::zero() && !shows_value) {
+        my_fields.push(Field {
             name: "Amount",
             value: value_with_unit.as_str(),
-        },
-        Field {
-            name: "To",
-            value: to_str.as_str(),
-        },
-        Field {
-            name: "Max Gas Fees",
-            value: fee_with_unit.as_str(),
-        },
-    ];
+        });
+    }
+    // The recipient of contract calls is part of the decoded fields
+    if call.is_none() {
+        if tx.is_contract_creation() {
+            my_fields.push(Field {
+                name: "Type",
+                value: "Contract deployment",
+            });
+            my_fields.push(Field {
+                name: "Init Code Size",
+                value: init_code_size.as_str(),
+            });
+            my_fields.push(Field {
+                name: "Init Code Hash",
+                value: init_code_hash.as_str(),
+            });
+        } else {
+            my_fields.push(Field {
+                name: "To",
+                value: to_str.as_str(),
+            });
+        }
+    }
+    my_fields.push(Field {
+        name: "Max Gas Fees",
+        value: fee_with_unit.as_str(),
+    });
 
     // If max storage fee is not zero, add it to the review fields
     let storage_fee_str = tx.max_storage_fee().cfx_str().ok_or(AppSW::TxDisplayFail)?;
@@ -80,8 +160,12 @@ pub fn ui_display_tx(tx: &Transaction, ctx: &mut TxContext) -> Result<bool, AppS
     }
 
     // If data is not empty, add it to the review fields
-    let data_str = format!("0x{}", hex::encode(tx.data.clone()).to_uppercase());
-    if !tx.data.is_empty() {
+    let has_data_field = call.is_none() && !tx.data.is_empty();
+    let data_str = match has_data_field {
+        true => data_hex(&tx.data, tx.data_truncated())?,
+        false => String::new(),
+    };
+    if has_data_field {
         my_fields.push(Field {
             name: "Data",
             value: data_str.as_str(),
@@ -109,6 +193,13 @@ pub fn ui_display_tx(tx: &Transaction, ctx: &mut TxContext) -> Result<bool, AppS
             warning.place_and_wait();
         }
 
+        // show the warning of dangerous operations
+        if let Some(warning) = call.and_then(|call| call.warning) {
+            let warning = Page::from((warning.lines, &WARNING));
+            clear_screen();
+            warning.place_and_wait();
+        }
+
         let my_review = MultiFieldReview::new(
             &my_fields,
             &["Review ", "Transaction"],
@@ -139,6 +230,18 @@ pub fn ui_display_tx(tx: &Transaction, ctx: &mut TxContext) -> Result<bool, AppS
         } else {
             ctx.home.set_start_page(PageIndex::Home);
         }
+
+        // Dangerous operations are only reviewed once their warning is acknowledged
+        if let Some(warning) = call.and_then(|call| call.warning) {
+            if !NbglChoice::new().show(
+                warning.title,
+                warning.text,
+                "Continue",
+                "Reject transaction",
+            ) {
+                return Ok(false);
+            }
+        }
         // Load glyph from 64x64 4bpp gif file with include_gif macro. Creates an NBGL compatible glyph.
         #[cfg(any(target_os = "stax", target_os = "flex"))]
         const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_64.gif", NBGL));
@@ -146,20 +249,35 @@ pub fn ui_display_tx(tx: &Transaction, ctx: &mut TxContext) -> Result<bool, AppS
         const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_48.png", NBGL));
         // Create NBGL review. Maximum number of fields and string buffer length can be customised
         // with constant generic parameters of NbglReview. Default values are 32 and 1024 respectively.
-        let mut review: NbglReview = NbglReview::new()
-            .titles(
+        let call_titles = call.map(|call| {
+            (
+                format!("Review transaction\nto {}", call.operation),
+                format!("Sign transaction\nto {}", call.operation),
+            )
+        });
+        let mut review: NbglReview = if let Some((review_title, sign_title)) = &call_titles {
+            NbglReview::new().titles(review_title, "", sign_title)
+        } else if tx.is_contract_creation() {
+            NbglReview::new().titles(
+                "Review transaction\nto deploy a contract",
+                "",
+                "Sign transaction\nto deploy a contract",
+            )
+        } else {
+            NbglReview::new().titles(
                 "Review transaction\nto send CFX",
                 "",
                 "Sign transaction\nto send CFX",
             )
-            .glyph(&CFX);
+        }
+        .glyph(&CFX);
 
         if !fully_decoded {
             review = review.blind();
         }
 
         // If second setting switch is disabled do not display the transaction data
-        if settings.get_element(1)? == 0 && !tx.data.is_empty() {
+        if settings.get_element(1)? == 0 && has_data_field {
             let field_len = my_fields.len() - 1;
             Ok(review.show(&my_fields[0..field_len]))
         } else {
@@ -167,3 +285,16 @@ pub fn ui_display_tx(tx: &Transaction, ctx: &mut TxContext) -> Result<bool, AppS
         }
     }
 }
+
+/// Formats the transaction data as `0x` followed by uppercase hex, encoded in
+/// a single buffer as the data can be up to 1 KiB.
+fn data_hex(data: &[u8], truncated: bool) -> Result<String, AppSW> {
+    let mut buf = vec![0u8; 2 + 2 * data.len()];
+    buf[..2].copy_from_slice(b"0x");
+    hex::encode_to_slice(data, &mut buf[2..]).map_err(|_| AppSW::TxDisplayFail)?;
+    buf[2..].make_ascii_uppercase();
+    if truncated {
+        buf.extend_from_slice(b"...");
+    }
+    String::from_utf8(buf).map_err(|_| AppSW::TxDisplayFail)
+}
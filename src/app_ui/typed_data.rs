@@ -0,0 +1,190 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::cfx_addr::Network;
+use crate::consts::HASH_BYTES_LEN;
+use crate::handlers::sign_tx::TxContext;
+use crate::settings::Settings;
+use crate::types::Cip23Context;
+use crate::AppSW;
+use alloc::{format, string::String, vec::Vec};
+
+#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+use ledger_device_sdk::ui::{
+    bitmaps::{CROSSMARK, EYE, VALIDATE_14, WARNING},
+    gadgets::{clear_screen, Field, MultiFieldReview, Page},
+};
+
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+use ledger_device_sdk::include_gif;
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+use ledger_device_sdk::nbgl::{
+    Field, NbglChoice, NbglGlyph, NbglReview, PageIndex, TransactionType,
+};
+
+/// Displays the domain separator and message hashes of CIP-23 typed data
+/// and returns true if user approved them.
+///
+/// The typed data content cannot be shown, so this review requires the
+/// blind signing setting to be enabled.
+///
+/// # Arguments
+///
+/// * `domain_hash` - Domain separator hash
+/// * `message_hash` - `hashStruct` of the message
+#[allow(unused_variables)]
+pub fn ui_display_typed_data_hashes(
+    domain_hash: &[u8; HASH_BYTES_LEN],
+    message_hash: &[u8; HASH_BYTES_LEN],
+    ctx: &mut TxContext,
+) -> Result<bool, AppSW> {
+    let domain_str = format!("0x{}", hex::encode(domain_hash).to_uppercase());
+    let message_str = format!("0x{}", hex::encode(message_hash).to_uppercase());
+
+    let my_fields = [
+        Field {
+            name: "Domain hash",
+            value: domain_str.as_str(),
+        },
+        Field {
+            name: "Message hash",
+            value: message_str.as_str(),
+        },
+    ];
+
+    let settings: Settings = Default::default();
+
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    {
+        if settings.get_element(0)? == 0 {
+            // show warning and return
+            let warning =
+                Page::from((["Blind signing must", "be enabled in Settings"], &CROSSMARK));
+            clear_screen();
+            warning.place_and_wait();
+            return Ok(false);
+        }
+
+        // show warning
+        let warning = Page::from((["Message content", "cannot be shown"], &WARNING));
+        clear_screen();
+        warning.place_and_wait();
+
+        let my_review = MultiFieldReview::new(
+            &my_fields,
+            &["Review ", "Typed Message"],
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+        Ok(my_review.show())
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+    {
+        if settings.get_element(0)? == 0 {
+            let confirmed = NbglChoice::new().show(
+                "This message cannot be clear-signed",
+                "Enable blind signing in the settings to sign this message.",
+                "Go to settings",
+                "Reject message",
+            );
+
+            if confirmed {
+                ctx.home.set_start_page(PageIndex::Settings(0));
+            }
+
+            return Ok(false);
+        } else {
+            ctx.home.set_start_page(PageIndex::Home);
+        }
+        // Load glyph from 64x64 4bpp gif file with include_gif macro. Creates an NBGL compatible glyph.
+        #[cfg(any(target_os = "stax", target_os = "flex"))]
+        const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_64.gif", NBGL));
+        #[cfg(target_os = "apex_p")]
+        const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_48.png", NBGL));
+
+        let review: NbglReview = NbglReview::new()
+            .tx_type(TransactionType::Message)
+            .titles("Review typed message", "", "Sign typed message")
+            .glyph(&CFX)
+            .blind();
+
+        Ok(review.show(&my_fields))
+    }
+}
+
+/// Displays the fields of CIP-23 typed data streamed to the device and
+/// returns true if user approved them.
+///
+/// Addresses are displayed in base32 with the network of the domain `chainId`.
+///
+/// # Arguments
+///
+/// * `cip23` - Typed data received through the struct definition and implementation APDUs
+#[allow(unused_variables)]
+pub fn ui_display_typed_data(cip23: &Cip23Context, ctx: &mut TxContext) -> Result<bool, AppSW> {
+    let network = Network::from_network_id(cip23.chain_id.ok_or(AppSW::BadState)?);
+
+    let values = cip23
+        .fields
+        .iter()
+        .map(|field| field.value.format(network, false))
+        .collect::<Result<Vec<String>, AppSW>>()?;
+
+    let my_fields: Vec<Field> = cip23
+        .fields
+        .iter()
+        .zip(values.iter())
+        .map(|(field, value)| Field {
+            name: field.name.as_str(),
+            value: value.as_str(),
+        })
+        .collect();
+
+    #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
+    {
+        let my_review = MultiFieldReview::new(
+            &my_fields,
+            &["Review ", "Typed Message"],
+            Some(&EYE),
+            "Approve",
+            Some(&VALIDATE_14),
+            "Reject",
+            Some(&CROSSMARK),
+        );
+        Ok(my_review.show())
+    }
+
+    #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+    {
+        ctx.home.set_start_page(PageIndex::Home);
+        // Load glyph from 64x64 4bpp gif file with include_gif macro. Creates an NBGL compatible glyph.
+        #[cfg(any(target_os = "stax", target_os = "flex"))]
+        const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_64.gif", NBGL));
+        #[cfg(target_os = "apex_p")]
+        const CFX: NbglGlyph = NbglGlyph::from_include(include_gif!("icons/cfx_48.png", NBGL));
+
+        let review: NbglReview = NbglReview::new()
+            .tx_type(TransactionType::Message)
+            .titles("Review typed message", "", "Sign typed message")
+            .glyph(&CFX);
+
+        Ok(review.show(&my_fields))
+    }
+}
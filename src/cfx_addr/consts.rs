@@ -18,6 +18,15 @@ pub const CHARSET: [char; 32] = [
 // network prefix
 pub const MAINNET_PREFIX: &str = "cfx";
 pub const TESTNET_PREFIX: &str = "cfxtest";
+pub const NETWORK_ID_PREFIX: &str = "net";
+
+// address type option of the verbose form, e.g. "type.user"
+pub const TYPE_OPTION_PREFIX: &str = "type.";
+pub const ADDRESS_TYPE_BUILTIN: &str = "builtin";
+pub const ADDRESS_TYPE_CONTRACT: &str = "contract";
+pub const ADDRESS_TYPE_NULL: &str = "null";
+pub const ADDRESS_TYPE_USER: &str = "user";
+pub const ADDRESS_TYPE_UNKNOWN: &str = "unknown";
 
 pub const MAIN_NET_ID: u64 = 1029;
 pub const TEST_NET_ID: u64 = 1;
@@ -6,7 +6,18 @@ use consts::*;
 pub use types::*;
 use utils::*;
 
-use alloc::{string::String, vec::Vec};
+use alloc::{format, string::String, vec::Vec};
+
+// Length of the checksum, in 5-bit groups.
+const CHECKSUM_LEN: usize = 8;
+
+/// Address decoded from its base32 form.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DecodedAddress {
+    pub raw: Vec<u8>,
+    pub network: Network,
+    pub address_type: AddressType,
+}
 
 pub fn cfx_addr_encode(raw: &[u8], network: Network) -> Result<String, EncodingError> {
     // Calculate version byte
@@ -49,10 +60,188 @@ pub fn cfx_addr_encode(raw: &[u8], network: Network) -> Result<String, EncodingE
     // Convert checksum to string
     let checksum_str: String = (0..8)
         .rev()
-        .map(|i| CHARSET[((checksum >> (i * 5)) & 31) as usize] as char)
+        .map(|i| CHARSET[((checksum >> (i * 5)) & 31) as usize])
         .collect();
 
     let cfx_base32_addr = [&prefix, ":", &payload_str, &checksum_str].concat();
 
     Ok(cfx_base32_addr)
 }
+
+/// Decodes a base32 address, either `<prefix>:<payload>` or the verbose
+/// `<PREFIX>:TYPE.<TYPE>:<PAYLOAD>` form, and verifies its checksum.
+pub fn cfx_addr_decode(addr: &str) -> Result<DecodedAddress, EncodingError> {
+    // Addresses are either all lowercase or all uppercase
+    let has_lowercase = addr.chars().any(|c| c.is_ascii_lowercase());
+    let has_uppercase = addr.chars().any(|c| c.is_ascii_uppercase());
+    if has_lowercase && has_uppercase {
+        return Err(EncodingError::MixedCase);
+    }
+    let addr = addr.to_ascii_lowercase();
+
+    let parts: Vec<&str> = addr.split(':').collect();
+    let (prefix, option, payload_str) = match parts.as_slice() {
+        [prefix, payload] => (*prefix, None, *payload),
+        [prefix, option, payload] => (*prefix, Some(*option), *payload),
+        _ => return Err(EncodingError::Prefix(addr.clone())),
+    };
+    let network = Network::from_prefix(prefix)?;
+
+    // Convert payload string to 5 bit array
+    let payload_5_bits = payload_str
+        .chars()
+        .map(|c| {
+            CHARSET
+                .iter()
+                .position(|x| *x == c)
+                .map(|b| b as u8)
+                .ok_or(EncodingError::Charset(c))
+        })
+        .collect::<Result<Vec<u8>, EncodingError>>()?;
+    if payload_5_bits.len() <= CHECKSUM_LEN {
+        return Err(EncodingError::Length(payload_5_bits.len()));
+    }
+
+    // Verify checksum
+    let expanded_prefix = expand_prefix(prefix);
+    let checksum_input = [&expanded_prefix[..], &payload_5_bits].concat();
+    if polymod(&checksum_input) != 0 {
+        return Err(EncodingError::Checksum);
+    }
+
+    // Convert payload back to bytes
+    let data_5_bits = &payload_5_bits[..payload_5_bits.len() - CHECKSUM_LEN];
+    let payload = convert_bits(data_5_bits, 5, 8, false).map_err(|_e| EncodingError::Padding)?;
+
+    // Conflux only has 160 bits hash size
+    let (version_byte, raw) = payload.split_first().ok_or(EncodingError::Length(0))?;
+    if *version_byte != consts::SIZE_160 {
+        return Err(EncodingError::VersionByte(*version_byte));
+    }
+    if raw.len() != 20 {
+        return Err(EncodingError::Length(raw.len()));
+    }
+
+    // The verbose form type must match the address
+    let address_type = AddressType::from_address(raw);
+    if let Some(option) = option {
+        if option != format!("{}{}", TYPE_OPTION_PREFIX, address_type.to_str()) {
+            return Err(EncodingError::Option(option.into()));
+        }
+    }
+
+    Ok(DecodedAddress {
+        raw: raw.to_vec(),
+        network,
+        address_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_ADDRESS: &str = "106d49f8505410eb4e671d51f7d96d2c87807b09";
+
+    #[test]
+    fn decode_networks() {
+        let raw = hex::decode(USER_ADDRESS).unwrap();
+        for (addr, network) in [
+            (
+                "cfx:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7p",
+                Network::Main,
+            ),
+            (
+                "cfxtest:aajg4wt2mbmbb44sp6szd783ry0jtad5bemzfdf83g",
+                Network::Test,
+            ),
+            (
+                "net17:aajg4wt2mbmbb44sp6szd783ry0jtad5bevscwk4s6",
+                Network::Id(17),
+            ),
+        ] {
+            let decoded = cfx_addr_decode(addr).unwrap();
+            assert_eq!(decoded.raw, raw);
+            assert_eq!(decoded.network, network);
+            assert_eq!(decoded.address_type, AddressType::User);
+            assert_eq!(cfx_addr_encode(&decoded.raw, network).unwrap(), addr);
+        }
+    }
+
+    #[test]
+    fn decode_address_types() {
+        for (addr, address_type) in [
+            (
+                "cfx:acag4wt2mbmbb44sp6szd783ry0jtad5bex25t8vc9",
+                AddressType::Contract,
+            ),
+            (
+                "cfx:aaag4wt2mbmbb44sp6szd783ry0jtad5beaar3k429",
+                AddressType::Builtin,
+            ),
+            (
+                "cfx:aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa0sfbnjm2",
+                AddressType::Null,
+            ),
+        ] {
+            assert_eq!(cfx_addr_decode(addr).unwrap().address_type, address_type);
+        }
+    }
+
+    #[test]
+    fn decode_verbose() {
+        let decoded =
+            cfx_addr_decode("CFX:TYPE.USER:AAJG4WT2MBMBB44SP6SZD783RY0JTAD5BEA80XDY7P").unwrap();
+        assert_eq!(decoded.raw, hex::decode(USER_ADDRESS).unwrap());
+        assert_eq!(decoded.network, Network::Main);
+
+        assert_eq!(
+            cfx_addr_decode("CFX:TYPE.CONTRACT:AAJG4WT2MBMBB44SP6SZD783RY0JTAD5BEA80XDY7P"),
+            Err(EncodingError::Option("type.contract".into()))
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(
+            cfx_addr_decode("cfx:AAJG4WT2MBMBB44SP6SZD783RY0JTAD5BEA80XDY7P"),
+            Err(EncodingError::MixedCase)
+        );
+        assert_eq!(
+            cfx_addr_decode("cfx:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7o"),
+            Err(EncodingError::Charset('o'))
+        );
+        assert_eq!(
+            cfx_addr_decode("cfx:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7q"),
+            Err(EncodingError::Charset('q'))
+        );
+        assert_eq!(
+            cfx_addr_decode("cfx:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7r"),
+            Err(EncodingError::Checksum)
+        );
+        assert_eq!(
+            cfx_addr_decode("cfxtest:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7p"),
+            Err(EncodingError::Checksum)
+        );
+        assert_eq!(
+            cfx_addr_decode("bch:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7p"),
+            Err(EncodingError::Prefix("bch".into()))
+        );
+        assert_eq!(
+            cfx_addr_decode("net1029:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7p"),
+            Err(EncodingError::NetworkId(1029))
+        );
+        assert_eq!(
+            cfx_addr_decode("cfx:bajg4wt2mbmbb44sp6szd783ry0jtad5be15n6e1gv"),
+            Err(EncodingError::VersionByte(8))
+        );
+        assert_eq!(
+            cfx_addr_decode("cfx:aajg4wt2mbmbb44sp6szd783ry0jtad5bfx7t241sr"),
+            Err(EncodingError::Padding)
+        );
+        assert!(matches!(
+            cfx_addr_decode("aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7p"),
+            Err(EncodingError::Prefix(_))
+        ));
+    }
+}
@@ -1,5 +1,7 @@
 use super::consts::{
-    MAINNET_PREFIX, MAIN_NET_ID, RESERVED_NETWORK_IDS, TESTNET_PREFIX, TEST_NET_ID,
+    ADDRESS_TYPE_BUILTIN, ADDRESS_TYPE_CONTRACT, ADDRESS_TYPE_NULL, ADDRESS_TYPE_UNKNOWN,
+    ADDRESS_TYPE_USER, MAINNET_PREFIX, MAIN_NET_ID, NETWORK_ID_PREFIX, RESERVED_NETWORK_IDS,
+    TESTNET_PREFIX, TEST_NET_ID,
 };
 use alloc::{format, string::String};
 use core::fmt;
@@ -36,13 +38,80 @@ impl Network {
             _ => Self::Id(network_id),
         }
     }
+
+    /// Parses a lowercase address prefix: `cfx`, `cfxtest` or `net<id>`.
+    pub fn from_prefix(prefix: &str) -> Result<Self, EncodingError> {
+        match prefix {
+            MAINNET_PREFIX => Ok(Network::Main),
+            TESTNET_PREFIX => Ok(Network::Test),
+            _ => {
+                let network_id = prefix
+                    .strip_prefix(NETWORK_ID_PREFIX)
+                    // Only the canonical decimal form is accepted
+                    .filter(|id| !id.starts_with('0') && id.bytes().all(|b| b.is_ascii_digit()))
+                    .and_then(|id| id.parse::<u64>().ok())
+                    .ok_or_else(|| EncodingError::Prefix(prefix.into()))?;
+                if RESERVED_NETWORK_IDS.contains(&network_id) {
+                    Err(EncodingError::NetworkId(network_id))
+                } else {
+                    Ok(Network::Id(network_id))
+                }
+            }
+        }
+    }
 }
 
-/// Error concerning encoding of cfx_base32_addr.
+/// Type of an address, given by the first 4 bits of its hex form.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum AddressType {
+    Builtin,
+    Contract,
+    Null,
+    User,
+    Unknown,
+}
+
+impl AddressType {
+    pub fn from_address(raw: &[u8]) -> Self {
+        if raw.iter().all(|b| *b == 0) {
+            return AddressType::Null;
+        }
+        match raw.first().map(|b| b & 0xf0) {
+            Some(0x00) => AddressType::Builtin,
+            Some(0x10) => AddressType::User,
+            Some(0x80) => AddressType::Contract,
+            _ => AddressType::Unknown,
+        }
+    }
+
+    pub fn to_str(self) -> &'static str {
+        match self {
+            AddressType::Builtin => ADDRESS_TYPE_BUILTIN,
+            AddressType::Contract => ADDRESS_TYPE_CONTRACT,
+            AddressType::Null => ADDRESS_TYPE_NULL,
+            AddressType::User => ADDRESS_TYPE_USER,
+            AddressType::Unknown => ADDRESS_TYPE_UNKNOWN,
+        }
+    }
+}
+
+/// Error concerning encoding or decoding of cfx_base32_addr.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum EncodingError {
     Length(usize),
     NetworkId(u64),
+    /// Character outside of the base32 charset.
+    Charset(char),
+    Checksum,
+    /// Both lowercase and uppercase characters.
+    MixedCase,
+    VersionByte(u8),
+    /// Non-zero padding bits, or too many of them.
+    Padding,
+    /// Unknown network prefix, or no prefix.
+    Prefix(String),
+    /// Unknown option in the verbose form, or address type mismatch.
+    Option(String),
 }
 
 impl fmt::Display for EncodingError {
@@ -54,6 +123,27 @@ impl fmt::Display for EncodingError {
             Self::NetworkId(network_id) => {
                 write!(f, "invalid network_id (reserved: {})", network_id)
             }
+            Self::Charset(c) => {
+                write!(f, "invalid character ({})", c)
+            }
+            Self::Checksum => {
+                write!(f, "invalid checksum")
+            }
+            Self::MixedCase => {
+                write!(f, "mixed case string")
+            }
+            Self::VersionByte(version) => {
+                write!(f, "invalid version byte ({})", version)
+            }
+            Self::Padding => {
+                write!(f, "invalid padding")
+            }
+            Self::Prefix(prefix) => {
+                write!(f, "invalid prefix ({})", prefix)
+            }
+            Self::Option(option) => {
+                write!(f, "invalid option ({})", option)
+            }
         }
     }
 }
@@ -3,9 +3,20 @@ pub const ADDRRESS_BYTES_LEN: usize = 20;
 pub const HASH_BYTES_LEN: usize = 32;
 
 /**
- * Maximum transaction length (bytes).
+ * Maximum transaction data length kept for display (bytes).
+ * Longer data is still hashed and signed, only its first bytes are shown.
  */
-pub const MAX_TRANSACTION_LEN: usize = 765;
+pub const MAX_TX_DATA_LEN: usize = 1024;
+
+/**
+ * Maximum personal message length (bytes).
+ */
+pub const MAX_MESSAGE_LEN: usize = 765;
+
+/**
+ * Prefix prepended to personal messages before hashing.
+ */
+pub const PERSONAL_MESSAGE_PREFIX: &[u8] = b"\x19Conflux Signed Message:\n";
 
 /**
  * Exponent used to convert Drip to CFX unit (N CFX = N * 10^18 Drip).
@@ -23,3 +34,29 @@ pub const APP_FLAG_DETAILED_DISPLAY_ENABLED: u8 = 0x02;
  * One CFX that can be paid for storage.
  */
 pub const STORAGE_OF_ONE_CFX: u64 = 1024;
+
+/*
+ * Public key verifying token metadata records, call descriptors and trusted
+ * names, set at build time with the TOKEN_SIGNER_PUBKEY environment variable.
+ */
+include!(concat!(env!("OUT_DIR"), "/token_signer.rs"));
+
+/**
+ * Maximum number of token metadata records kept in memory.
+ */
+pub const MAX_TOKEN_INFOS: usize = 5;
+
+/**
+ * Maximum number of call descriptors kept in memory.
+ */
+pub const MAX_CALL_DESCRIPTORS: usize = 3;
+
+/**
+ * Maximum number of trusted names kept in memory.
+ */
+pub const MAX_TRUSTED_NAMES: usize = 5;
+
+/**
+ * Maximum number of contacts saved in the address book.
+ */
+pub const MAX_CONTACTS: usize = 10;
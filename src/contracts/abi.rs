@@ -0,0 +1,222 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::consts::ADDRRESS_BYTES_LEN;
+use crate::types::U256;
+
+// Size of an ABI word.
+pub const WORD_LEN: usize = 32;
+
+/// ABI encoded arguments of a contract call, following the 4-byte selector.
+pub struct Args<'a>(&'a [u8]);
+
+impl<'a> Args<'a> {
+    /// Wraps the arguments of a function taking `count` static arguments.
+    ///
+    /// Any trailing data is rejected, so that nothing is hidden from the review.
+    pub fn new(data: &'a [u8], count: usize) -> Option<Self> {
+        (data.len() == count * WORD_LEN).then_some(Args(data))
+    }
+
+    /// Wraps the arguments of a function taking `count` arguments, the last of
+    /// them being dynamic.
+    ///
+    /// The content of the dynamic argument follows the arguments, it is read
+    /// with [`Args::array`] or [`Args::bytes`], or with [`Args::tails`] when
+    /// there are several dynamic arguments.
+    pub fn with_dynamic(data: &'a [u8], count: usize) -> Option<Self> {
+        (data.len() > count * WORD_LEN).then_some(Args(data))
+    }
+
+    /// Number of words of the arguments.
+    pub fn word_count(&self) -> usize {
+        self.0.len() / WORD_LEN
+    }
+
+    /// Reads the dynamic array at `index`, the last argument, made of items
+    /// of `item_len` words.
+    pub fn array(&self, index: usize, item_len: usize) -> Option<Args<'a>> {
+        let mut tails = self.tails(index + 1);
+        let items = tails.array(index, item_len)?;
+        tails.end().then_some(items)
+    }
+
+    /// Reads the dynamic byte string at `index`, the last argument.
+    pub fn bytes(&self, index: usize) -> Option<&'a [u8]> {
+        let mut tails = self.tails(index + 1);
+        let bytes = tails.bytes(index)?;
+        tails.end().then_some(bytes)
+    }
+
+    /// Reads the dynamic arguments of a function taking `count` arguments.
+    pub fn tails(&self, count: usize) -> Tails<'a> {
+        Tails {
+            args: Args(self.0),
+            next: count * WORD_LEN,
+        }
+    }
+
+    /// Reads the byte string at the offset given by the argument at `index`,
+    /// without checking what surrounds it.
+    ///
+    /// Only for functions with several dynamic arguments, whose content is
+    /// not displayed in full anyway.
+    pub fn bytes_at(&self, index: usize) -> Option<&'a [u8]> {
+        let offset = self.usize(index)?;
+        let len = Args(&self.0[offset..]).usize(0)?;
+        self.0.get(offset + WORD_LEN..offset + WORD_LEN + len)
+    }
+
+    pub fn word(&self, index: usize) -> Option<&'a [u8]> {
+        self.0.get(index * WORD_LEN..(index + 1) * WORD_LEN)
+    }
+
+    /// Reads an address, its 12 padding bytes must be zero.
+    pub fn address(&self, index: usize) -> Option<[u8; ADDRRESS_BYTES_LEN]> {
+        let word = self.word(index)?;
+        let (padding, addr) = word.split_at(WORD_LEN - ADDRRESS_BYTES_LEN);
+        if padding.iter().any(|b| *b != 0) {
+            return None;
+        }
+        addr.try_into().ok()
+    }
+
+    /// Reads a `bytes20` value, its 12 padding bytes must be zero.
+    pub fn bytes20(&self, index: usize) -> Option<[u8; ADDRRESS_BYTES_LEN]> {
+        let word = self.word(index)?;
+        let (value, padding) = word.split_at(ADDRRESS_BYTES_LEN);
+        if padding.iter().any(|b| *b != 0) {
+            return None;
+        }
+        value.try_into().ok()
+    }
+
+    /// Reads a `bool` value, either 0 or 1.
+    pub fn bool(&self, index: usize) -> Option<bool> {
+        match self.uint(index)? {
+            v if v.is_zero() => Some(false),
+            v if v == U256::one() => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn uint(&self, index: usize) -> Option<U256> {
+        self.word(index).map(U256::from_big_endian)
+    }
+
+    /// Reads a `uint64` value, which must fit in 64 bits.
+    pub fn u64(&self, index: usize) -> Option<u64> {
+        let value = self.uint(index)?;
+        (value <= U256::from(u64::MAX)).then(|| value.as_u64())
+    }
+
+    // Reads a length or an offset
+    fn usize(&self, index: usize) -> Option<usize> {
+        let value = self.uint(index)?;
+        (value <= U256::from(self.0.len())).then(|| value.as_usize())
+    }
+}
+
+/// Reader of the content of dynamic arguments.
+///
+/// The contents must directly follow the arguments in the order they are read
+/// and end the data, so that nothing is hidden from the review.
+pub struct Tails<'a> {
+    args: Args<'a>,
+    // Offset expected for the next content
+    next: usize,
+}
+
+impl<'a> Tails<'a> {
+    /// Reads the dynamic array at `index`, made of items of `item_len` words.
+    pub fn array(&mut self, index: usize, item_len: usize) -> Option<Args<'a>> {
+        let (count, content) = self.content(index)?;
+        let items = content.get(..count.checked_mul(item_len * WORD_LEN)?)?;
+        self.next += WORD_LEN + items.len();
+        Some(Args(items))
+    }
+
+    /// Reads the dynamic byte string at `index`, its padding must be zero.
+    pub fn bytes(&mut self, index: usize) -> Option<&'a [u8]> {
+        let (len, content) = self.content(index)?;
+        let padded = content.get(..len.div_ceil(WORD_LEN) * WORD_LEN)?;
+        let (bytes, padding) = padded.split_at(len);
+        if padding.iter().any(|b| *b != 0) {
+            return None;
+        }
+        self.next += WORD_LEN + padded.len();
+        Some(bytes)
+    }
+
+    /// Reads the dynamic array at `index`, made of dynamic items.
+    ///
+    /// Returns the number of items and the reader of their content, which
+    /// must be given back to [`Tails::close`] once all the items are read.
+    pub fn dynamic_array(&mut self, index: usize) -> Option<(usize, Tails<'a>)> {
+        let (count, content) = self.content(index)?;
+        let items = Args(content);
+        if items.word_count() < count {
+            return None;
+        }
+        Some((count, items.tails(count)))
+    }
+
+    /// Reads the dynamic tuple at `index`, whose head is made of `count`
+    /// words.
+    ///
+    /// Returns the head of the tuple and the reader of its dynamic content,
+    /// which must be given back to [`Tails::close`] once read.
+    pub fn tuple(&mut self, index: usize, count: usize) -> Option<(Args<'a>, Tails<'a>)> {
+        let offset = self.args.usize(index)?;
+        if offset != self.next {
+            return None;
+        }
+        let tuple = &self.args.0[offset..];
+        let head = tuple.get(..count * WORD_LEN)?;
+        Some((Args(head), Args(tuple).tails(count)))
+    }
+
+    /// Ends the reading of a nested content, opened with
+    /// [`Tails::dynamic_array`] or [`Tails::tuple`].
+    pub fn close(&mut self, nested: Tails<'a>) {
+        // Nested contents extend to the end of the data
+        self.next = self.args.0.len() - nested.args.0.len() + nested.next;
+    }
+
+    /// Whether all the data was read.
+    pub fn end(&self) -> bool {
+        self.next == self.args.0.len()
+    }
+
+    // Reads the length of the content of the argument at `index`, and the
+    // data from the start of the content.
+    fn content(&self, index: usize) -> Option<(usize, &'a [u8])> {
+        let offset = self.args.usize(index)?;
+        if offset != self.next {
+            return None;
+        }
+        let len = Args(&self.args.0[offset..]).usize(0)?;
+        Some((len, &self.args.0[offset + WORD_LEN..]))
+    }
+}
+
+/// Left pads `bytes` to an ABI word.
+#[cfg(test)]
+pub fn word(bytes: &[u8]) -> [u8; WORD_LEN] {
+    let mut word = [0u8; WORD_LEN];
+    word[WORD_LEN - bytes.len()..].copy_from_slice(bytes);
+    word
+}
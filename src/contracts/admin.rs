@@ -0,0 +1,103 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{DecodedCall, Warning};
+use crate::consts::ADDRRESS_BYTES_LEN;
+
+/// Address of the AdminControl internal contract.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00,
+];
+
+// setAdmin(address,address)
+const SET_ADMIN: [u8; 4] = [0xc5, 0x5b, 0x6b, 0xb7];
+// destroy(address)
+const DESTROY: [u8; 4] = [0x00, 0xf5, 0x5d, 0x9d];
+
+const SET_ADMIN_WARNING: Warning = Warning {
+    title: "Contract admin change",
+    lines: ["Admin change is", "irreversible"],
+    text: "Only the new admin will be able to manage or destroy this contract.",
+};
+
+const REMOVE_ADMIN_WARNING: Warning = Warning {
+    title: "Contract admin removal",
+    lines: ["Admin removal is", "irreversible"],
+    text: "Nobody will be able to manage or destroy this contract anymore.",
+};
+
+const DESTROY_WARNING: Warning = Warning {
+    title: "Contract destruction",
+    lines: ["Destruction is", "irreversible"],
+    text: "The code and storage of this contract will be deleted for good.",
+};
+
+/// Decodes the changes of contract admins and the destruction of contracts.
+///
+/// Both operations cannot be undone, a warning is displayed before their review.
+pub fn decode(selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    let call = match selector {
+        SET_ADMIN => {
+            let args = Args::new(args, 2)?;
+            let admin = args.address(1)?;
+            let call =
+                DecodedCall::new("change a contract admin").address("Contract", args.address(0)?);
+            // The zero address removes the admin
+            if admin == [0u8; ADDRRESS_BYTES_LEN] {
+                call.text("New admin", "None".into())
+                    .warning(&REMOVE_ADMIN_WARNING)
+            } else {
+                call.address("New admin", admin).warning(&SET_ADMIN_WARNING)
+            }
+        }
+        DESTROY => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("destroy a contract")
+                .address("Contract", args.address(0)?)
+                .warning(&DESTROY_WARNING)
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    #[test]
+    fn decode_set_admin() {
+        let args = [word(&[0x80; 20]), word(&[0x10; 20])].concat();
+        let call = decode(SET_ADMIN, &args).unwrap();
+        assert_eq!(call.fields[1].value, DisplayValue::Address([0x10; 20]));
+        assert_eq!(call.warning.unwrap().title, SET_ADMIN_WARNING.title);
+
+        let args = [word(&[0x80; 20]), word(&[])].concat();
+        let call = decode(SET_ADMIN, &args).unwrap();
+        assert_eq!(call.fields[1].value, DisplayValue::Text("None".into()));
+        assert_eq!(call.warning.unwrap().title, REMOVE_ADMIN_WARNING.title);
+    }
+
+    #[test]
+    fn decode_destroy() {
+        let call = decode(DESTROY, &word(&[0x80; 20])).unwrap();
+        assert_eq!(call.fields[0].value, DisplayValue::Address([0x80; 20]));
+        assert!(call.warning.is_some());
+    }
+}
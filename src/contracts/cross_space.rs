@@ -0,0 +1,110 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, DecodedCall};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use alloc::format;
+
+/// Address of the CrossSpaceCall internal contract.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x06,
+];
+
+// transferEVM(bytes20)
+const TRANSFER_EVM: [u8; 4] = [0xda, 0x8d, 0x5d, 0xaf];
+// callEVM(bytes20,bytes)
+const CALL_EVM: [u8; 4] = [0xbe, 0xa0, 0x5e, 0xe3];
+// createEVM(bytes)
+const CREATE_EVM: [u8; 4] = [0xff, 0x31, 0x16, 0x01];
+// withdrawFromMapped(uint256)
+const WITHDRAW_FROM_MAPPED: [u8; 4] = [0xc2, 0x3e, 0xf0, 0x31];
+
+/// Decodes the transfers and calls from Core to eSpace (CIP-90).
+///
+/// eSpace addresses are displayed in hex. The CFX sent to eSpace is the value
+/// of the transaction.
+pub fn decode(selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    let call = match selector {
+        TRANSFER_EVM => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("send to eSpace").espace_address("To", args.bytes20(0)?)
+        }
+        CALL_EVM => {
+            let args = Args::with_dynamic(args, 2)?;
+            // The eSpace call itself is not decoded
+            DecodedCall::new("call an eSpace contract")
+                .espace_address("Contract", args.bytes20(0)?)
+                .data(args.bytes(1)?)
+        }
+        CREATE_EVM => {
+            let args = Args::with_dynamic(args, 1)?;
+            let init_code = args.bytes(0)?;
+            DecodedCall::new("deploy an eSpace contract")
+                .text("Init Code Size", format!("{} bytes", init_code.len()))
+                .blind()
+        }
+        WITHDRAW_FROM_MAPPED => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("withdraw from eSpace")
+                .text("Withdraw", cfx_amount_str(args.uint(0)?)?)
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    fn bytes20(bytes: [u8; 20]) -> [u8; 32] {
+        let mut word = [0u8; 32];
+        word[..20].copy_from_slice(&bytes);
+        word
+    }
+
+    #[test]
+    fn decode_transfer_evm() {
+        let call = decode(TRANSFER_EVM, &bytes20([0x5a; 20])).unwrap();
+        assert_eq!(call.label(), "Send to eSpace");
+        assert_eq!(
+            call.fields[0].value,
+            DisplayValue::EspaceAddress([0x5a; 20])
+        );
+        assert!(!call.blind);
+        // Address encoded as an address, not as bytes20
+        assert!(decode(TRANSFER_EVM, &word(&[0x5a; 20])).is_none());
+    }
+
+    #[test]
+    fn decode_call_evm() {
+        let mut args = [bytes20([0x5a; 20]), word(&[0x40]), word(&[0x04]), word(&[])].concat();
+        args[96..100].copy_from_slice(&[0xa9, 0x05, 0x9c, 0xbb]);
+        let call = decode(CALL_EVM, &args).unwrap();
+        assert_eq!(
+            call.fields[1].value,
+            DisplayValue::Text("0xA9059CBB".into())
+        );
+        assert!(call.blind);
+
+        // Dirty padding after the data
+        args[100] = 1;
+        assert!(decode(CALL_EVM, &args).is_none());
+    }
+}
@@ -0,0 +1,184 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::DecodedCall;
+use crate::types::{Address, TokenInfo, U256};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+// transfer(address,uint256)
+const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+// transferFrom(address,address,uint256)
+const TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+// approve(address,uint256)
+const APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+// increaseAllowance(address,uint256)
+const INCREASE_ALLOWANCE: [u8; 4] = [0x39, 0x50, 0x93, 0x51];
+
+/// Decodes CRC-20 / ERC-20 token transfers and approvals.
+///
+/// Amounts of known tokens are displayed with their ticker and decimals.
+/// Otherwise they are displayed in the smallest unit of the token, next to
+/// the token contract address.
+pub fn decode(
+    token: &Address,
+    info: Option<&TokenInfo>,
+    selector: [u8; 4],
+    args: &[u8],
+) -> Option<DecodedCall> {
+    let ticker = info.map_or("tokens", |info| info.ticker.as_str());
+    let call = match selector {
+        TRANSFER => {
+            let args = Args::new(args, 2)?;
+            token_call(&format!("send {}", ticker), token, info)
+                .text("Send", amount_str(args.uint(1)?, info)?)
+                .address("To", args.address(0)?)
+        }
+        // CRC-721 tokens share this function, the value is a token ID for them
+        TRANSFER_FROM if info.is_none() => {
+            let args = Args::new(args, 3)?;
+            token_call("send tokens or an NFT", token, info)
+                .text("Amount or token ID", args.uint(2)?.to_string())
+                .address("From", args.address(0)?)
+                .address("To", args.address(1)?)
+        }
+        TRANSFER_FROM => {
+            let args = Args::new(args, 3)?;
+            token_call(&format!("send {}", ticker), token, info)
+                .text("Send", amount_str(args.uint(2)?, info)?)
+                .address("From", args.address(0)?)
+                .address("To", args.address(1)?)
+        }
+        APPROVE => {
+            let args = Args::new(args, 2)?;
+            token_call(&format!("approve {}", ticker), token, info)
+                .text("Approve", allowance_str(args.uint(1)?, info)?)
+                .address("Spender", args.address(0)?)
+        }
+        INCREASE_ALLOWANCE => {
+            let args = Args::new(args, 2)?;
+            let operation = match info {
+                Some(info) => format!("increase {} allowance", info.ticker),
+                None => "increase allowance".into(),
+            };
+            token_call(&operation, token, info)
+                .text("Increase allowance", allowance_str(args.uint(1)?, info)?)
+                .address("Spender", args.address(0)?)
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+// The ticker of a known token stands for its address
+fn token_call(operation: &str, token: &Address, info: Option<&TokenInfo>) -> DecodedCall {
+    let call = DecodedCall::new(operation);
+    match info {
+        Some(_) => call,
+        None => call.address("Token", token.0),
+    }
+}
+
+fn amount_str(amount: U256, info: Option<&TokenInfo>) -> Option<String> {
+    match info {
+        Some(info) => Some(format!(
+            "{} {}",
+            amount.decimal_str(info.decimals)?,
+            info.ticker
+        )),
+        None => Some(amount.to_string()),
+    }
+}
+
+// The maximum value is the usual encoding of an unlimited allowance
+fn allowance_str(amount: U256, info: Option<&TokenInfo>) -> Option<String> {
+    if amount != U256::MAX {
+        return amount_str(amount, info);
+    }
+    match info {
+        Some(info) => Some(format!("Unlimited {}", info.ticker)),
+        None => Some("Unlimited".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    const TOKEN: Address = Address([0x8b; 20]);
+
+    #[test]
+    fn decode_transfer() {
+        let args = [word(&[0x10; 20]), word(&[0x30, 0x39])].concat();
+        let call = decode(&TOKEN, None, TRANSFER, &args).unwrap();
+        assert_eq!(call.fields.len(), 3);
+        assert_eq!(call.fields[1].value, DisplayValue::Text("12345".into()));
+        assert_eq!(call.fields[2].value, DisplayValue::Address([0x10; 20]));
+        // The receiver is the recipient, not the token
+        assert_eq!(call.recipient(false), Some([0x10; 20]));
+
+        // Approvals have no recipient
+        let call = decode(&TOKEN, None, APPROVE, &args).unwrap();
+        assert_eq!(call.recipient(false), None);
+    }
+
+    #[test]
+    fn decode_unlimited_approve() {
+        let args = [word(&[0x10; 20]), [0xff; 32]].concat();
+        let call = decode(&TOKEN, None, APPROVE, &args).unwrap();
+        assert_eq!(call.fields[1].value, DisplayValue::Text("Unlimited".into()));
+    }
+
+    #[test]
+    fn decode_known_token() {
+        let info = TokenInfo {
+            address: TOKEN.0,
+            chain_id: 1029,
+            ticker: "USDT".into(),
+            decimals: 6,
+        };
+        let args = [word(&[0x10; 20]), word(&[0xbe, 0xbc, 0x20])].concat();
+        let call = decode(&TOKEN, Some(&info), TRANSFER, &args).unwrap();
+        assert_eq!(call.operation, "send USDT");
+        assert_eq!(call.fields.len(), 2);
+        assert_eq!(call.fields[0].value, DisplayValue::Text("12.5 USDT".into()));
+
+        let args = [word(&[0x10; 20]), [0xff; 32]].concat();
+        let call = decode(&TOKEN, Some(&info), APPROVE, &args).unwrap();
+        assert_eq!(
+            call.fields[0].value,
+            DisplayValue::Text("Unlimited USDT".into())
+        );
+    }
+
+    #[test]
+    fn reject_malformed_args() {
+        // Dirty address padding
+        let args = [word(&[0x01; 21]), word(&[0x01])].concat();
+        assert!(decode(&TOKEN, None, TRANSFER, &args).is_none());
+        // Trailing data
+        let args = [word(&[0x10; 20]), word(&[0x01]), word(&[0x01])].concat();
+        assert!(decode(&TOKEN, None, TRANSFER, &args).is_none());
+        // Unknown selector
+        let args = [word(&[0x10; 20]), word(&[0x01])].concat();
+        assert!(decode(&TOKEN, None, [0, 0, 0, 0], &args).is_none());
+    }
+}
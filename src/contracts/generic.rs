@@ -0,0 +1,150 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, date_str, Call, DecodedCall};
+use crate::types::{CallDescriptor, ParamFormat, ParamType, TokenCache, TokenRef};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
+
+/// Decodes a call with the descriptor of its function provided by the host.
+///
+/// The called contract is displayed first, followed by the parameters in
+/// their order. Amounts of unknown tokens are displayed in their smallest
+/// unit, next to the token contract address.
+pub fn decode(
+    call: &Call,
+    tokens: &TokenCache,
+    descriptor: &CallDescriptor,
+    args: &[u8],
+) -> Option<DecodedCall> {
+    let count = descriptor.params.len();
+    let dynamic = descriptor.params.iter().any(|p| p.kind.is_dynamic());
+    let args = match dynamic {
+        true => Args::with_dynamic(args, count)?,
+        false => Args::new(args, count)?,
+    };
+    let mut tails = args.tails(count);
+
+    let mut decoded = DecodedCall::new(&descriptor.operation).address("Contract", call.to.0);
+    for (i, param) in descriptor.params.iter().enumerate() {
+        let name = param.name.as_str();
+        decoded = match (param.kind, param.format) {
+            (ParamType::Uint, ParamFormat::Raw) => decoded.text(name, args.uint(i)?.to_string()),
+            (ParamType::Uint, ParamFormat::Amount) => {
+                decoded.text(name, cfx_amount_str(args.uint(i)?)?)
+            }
+            (ParamType::Uint, ParamFormat::Date) => decoded.text(name, date_str(args.uint(i)?)),
+            (ParamType::Uint, ParamFormat::TokenAmount(token)) => {
+                let token = match token {
+                    TokenRef::Contract => call.to.0,
+                    TokenRef::Param(index) => args.address(index)?,
+                };
+                let amount = args.uint(i)?;
+                match tokens.get(&token, call.tx.chain_id) {
+                    Some(info) => decoded.text(
+                        name,
+                        format!("{} {}", amount.decimal_str(info.decimals)?, info.ticker),
+                    ),
+                    None => decoded
+                        .address("Token", token)
+                        .text(name, amount.to_string()),
+                }
+            }
+            (ParamType::Address, _) => decoded.address(name, args.address(i)?),
+            (ParamType::Bool, _) => decoded.text(name, args.bool(i)?.to_string()),
+            (ParamType::Bytes32, _) => decoded.text(name, hex_str(args.word(i)?)),
+            (ParamType::Bytes, _) => decoded.text(name, hex_str(tails.bytes(i)?)),
+            (ParamType::String, _) => {
+                let text = tails.bytes(i)?;
+                // Only printable texts, they are displayed as is
+                if !text.iter().all(|c| *c == b' ' || c.is_ascii_graphic()) {
+                    return None;
+                }
+                decoded.text(name, String::from_utf8(text.into()).ok()?)
+            }
+        };
+    }
+    (!dynamic || tails.end()).then_some(decoded)
+}
+
+fn hex_str(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes).to_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::{Address, DescriptorRecord, DisplayValue, Transaction, U256};
+
+    // deposit(uint256 amount, address token, string memo)
+    fn descriptor() -> CallDescriptor {
+        let mut record = alloc::vec![0x00];
+        record.extend_from_slice(&1029u32.to_be_bytes());
+        record.extend_from_slice(&[0x8c; 20]);
+        record.extend_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+        record.extend_from_slice(b"\x07deposit\x03");
+        record.extend_from_slice(b"\x00\x03\x01\x06Amount");
+        record.extend_from_slice(b"\x01\x00\x05Token");
+        record.extend_from_slice(b"\x05\x00\x04Memo");
+        record.push(0x30);
+        DescriptorRecord::try_from(record.as_slice())
+            .unwrap()
+            .descriptor
+    }
+
+    #[test]
+    fn decode_with_descriptor() {
+        let descriptor = descriptor();
+        let tx = Transaction::default();
+        let call = Call {
+            tx: &tx,
+            to: Address([0x8c; 20]),
+            value: U256::zero(),
+            data: &[],
+        };
+        let mut memo = word(&[]);
+        memo[..5].copy_from_slice(b"hello");
+        let args = [
+            word(&[0x07]),
+            word(&[0xaa; 20]),
+            word(&[0x60]),
+            word(&[0x05]),
+            memo,
+        ]
+        .concat();
+        let decoded = decode(&call, &TokenCache::default(), &descriptor, &args).unwrap();
+        let values: alloc::vec::Vec<_> = decoded.fields.iter().map(|f| f.value.clone()).collect();
+        assert_eq!(
+            values,
+            [
+                DisplayValue::Address([0x8c; 20]),
+                DisplayValue::Address([0xaa; 20]),
+                DisplayValue::Text("7".into()),
+                DisplayValue::Address([0xaa; 20]),
+                DisplayValue::Text("hello".into()),
+            ]
+        );
+
+        // Text that cannot be displayed
+        let mut args = args;
+        args[4 * 32] = b'\n';
+        assert!(decode(&call, &TokenCache::default(), &descriptor, &args).is_none());
+    }
+}
@@ -0,0 +1,275 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+//! Clear signing of contract calls: the transaction data of known functions
+//! is decoded into review fields instead of being displayed as hex.
+mod abi;
+mod admin;
+mod cross_space;
+mod erc20;
+mod generic;
+mod multicall;
+mod nft;
+mod params_control;
+mod pos_register;
+mod sponsor;
+mod staking;
+mod swap;
+mod wcfx;
+
+use crate::consts::{ADDRRESS_BYTES_LEN, EXPONENT_SMALLEST_UNIT};
+use crate::types::{
+    Address, DescriptorCache, DisplayField, DisplayValue, TokenCache, Transaction, U256,
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+// Length of a function selector.
+const SELECTOR_LEN: usize = 4;
+
+/// Contract call decoded from the transaction data.
+pub struct DecodedCall {
+    /// What the transaction does, completing "Review transaction to ...".
+    pub operation: String,
+    pub fields: Vec<DisplayField>,
+    /// Whether the decoded call still carries content that cannot be
+    /// reviewed, such as the data of a nested call.
+    pub blind: bool,
+    /// Whether the value of the transaction is one of the fields.
+    pub shows_value: bool,
+    /// Warning displayed before the review of dangerous operations.
+    pub warning: Option<&'static Warning>,
+}
+
+/// Warning about a dangerous operation.
+pub struct Warning {
+    pub title: &'static str,
+    /// Title on two short lines, for Nano devices.
+    pub lines: [&'static str; 2],
+    pub text: &'static str,
+}
+
+impl DecodedCall {
+    /// Address of the single "To" field, when it is on the chain of the
+    /// request, e.g. the receiver of a token transfer.
+    pub fn recipient(&self, espace: bool) -> Option<[u8; ADDRRESS_BYTES_LEN]> {
+        let mut recipients = self.fields.iter().filter(|field| field.name == "To");
+        match (recipients.next(), recipients.next()) {
+            (Some(field), None) => field.value.chain_address(espace).copied(),
+            _ => None,
+        }
+    }
+
+    /// The operation as a standalone label, e.g. "Send to eSpace".
+    pub fn label(&self) -> String {
+        let mut chars = self.operation.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }
+
+    fn new(operation: &str) -> Self {
+        DecodedCall {
+            operation: operation.into(),
+            fields: Vec::new(),
+            blind: false,
+            shows_value: false,
+            warning: None,
+        }
+    }
+
+    fn warning(mut self, warning: &'static Warning) -> Self {
+        self.warning = Some(warning);
+        self
+    }
+
+    // Data passed on by the call, which cannot be decoded
+    fn data(self, data: &[u8]) -> Self {
+        if data.is_empty() {
+            return self;
+        }
+        self.text("Data", format!("0x{}", hex::encode(data).to_uppercase()))
+            .blind()
+    }
+
+    fn blind(mut self) -> Self {
+        self.blind = true;
+        self
+    }
+
+    fn text(mut self, name: &str, value: String) -> Self {
+        self.fields.push(DisplayField {
+            name: name.into(),
+            value: DisplayValue::Text(value),
+        });
+        self
+    }
+
+    fn address(mut self, name: &str, addr: [u8; ADDRRESS_BYTES_LEN]) -> Self {
+        self.fields.push(DisplayField {
+            name: name.into(),
+            value: DisplayValue::Address(addr),
+        });
+        self
+    }
+
+    // The value of the transaction, in CFX
+    fn value(mut self, name: &str, value: U256) -> Option<Self> {
+        self.shows_value = true;
+        Some(self.text(name, cfx_amount_str(value)?))
+    }
+
+    fn espace_address(mut self, name: &str, addr: [u8; ADDRRESS_BYTES_LEN]) -> Self {
+        self.fields.push(DisplayField {
+            name: name.into(),
+            value: DisplayValue::EspaceAddress(addr),
+        });
+        self
+    }
+}
+
+/// Contract call, made by the transaction or nested in a batch of calls.
+struct Call<'a> {
+    /// Transaction making the call, for its network.
+    tx: &'a Transaction,
+    to: Address,
+    /// CFX sent with the call.
+    value: U256,
+    data: &'a [u8],
+}
+
+/// Decodes the call made by a transaction, `None` if the function is unknown
+/// or the data was not entirely received.
+///
+/// Token amounts are formatted with the metadata of `tokens` when the called
+/// contract is a known token. Functions matching one of the `descriptors`
+/// are decoded as described.
+pub fn decode_call(
+    tx: &Transaction,
+    tokens: &TokenCache,
+    descriptors: &DescriptorCache,
+) -> Option<DecodedCall> {
+    if tx.data_truncated() {
+        return None;
+    }
+    let call = Call {
+        tx,
+        to: tx.to.clone()?,
+        value: tx.value,
+        data: &tx.data,
+    };
+    decode(&call, tokens, descriptors, false)
+}
+
+// Decodes a call, batches of calls are only decoded if not `nested`
+fn decode(
+    call: &Call,
+    tokens: &TokenCache,
+    descriptors: &DescriptorCache,
+    nested: bool,
+) -> Option<DecodedCall> {
+    if call.data.len() < SELECTOR_LEN {
+        return None;
+    }
+    let (selector, args) = call.data.split_at(SELECTOR_LEN);
+    let selector: [u8; SELECTOR_LEN] = selector.try_into().ok()?;
+    let (tx, to) = (call.tx, &call.to);
+
+    // Internal contracts only exist on Core
+    if !tx.is_espace() && to.is_builtin_address() {
+        return match to.0 {
+            admin::ADDRESS => admin::decode(selector, args),
+            cross_space::ADDRESS => cross_space::decode(selector, args),
+            params_control::ADDRESS => params_control::decode(selector, args),
+            pos_register::ADDRESS => pos_register::decode(selector, args),
+            sponsor::ADDRESS => sponsor::decode(selector, args),
+            staking::ADDRESS => staking::decode(selector, args),
+            _ => None,
+        };
+    }
+    // Core tokens are contracts, eSpace addresses carry no type
+    if tx.is_espace() || to.is_contract_address() {
+        if let Some(descriptor) = descriptors.get(&to.0, tx.chain_id, selector) {
+            return generic::decode(call, tokens, descriptor, args);
+        }
+        return erc20::decode(to, tokens.get(&to.0, tx.chain_id).as_ref(), selector, args)
+            .or_else(|| nft::decode(to, selector, args))
+            .or_else(|| wcfx::decode(call, selector, args))
+            .or_else(|| swap::decode(call, tokens, selector, args))
+            .or_else(|| match nested {
+                false => multicall::decode(call, tokens, descriptors, selector, args),
+                true => None,
+            });
+    }
+    None
+}
+
+/// Formats an amount of Drip in CFX.
+fn cfx_amount_str(amount: U256) -> Option<String> {
+    Some(format!(
+        "{} CFX",
+        amount.decimal_str(EXPONENT_SMALLEST_UNIT as u8)?
+    ))
+}
+
+/// Formats a Unix timestamp as a UTC date, or as a number if it is past the
+/// year 9999.
+fn date_str(timestamp: U256) -> String {
+    const SECONDS_PER_DAY: u64 = 86_400;
+    const MAX_TIMESTAMP: u64 = 253_402_300_799;
+    if timestamp > U256::from(MAX_TIMESTAMP) {
+        return timestamp.to_string();
+    }
+    let secs = timestamp.as_u64();
+    let (days, time) = (secs / SECONDS_PER_DAY, secs % SECONDS_PER_DAY);
+
+    // Civil date of a day count, from http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+/// Formats long byte strings as their first and last 4 bytes in hex.
+fn abbreviated_hex(bytes: &[u8]) -> String {
+    if bytes.len() <= 8 {
+        return format!("0x{}", hex::encode(bytes).to_uppercase());
+    }
+    format!(
+        "0x{}...{}",
+        hex::encode(&bytes[..4]).to_uppercase(),
+        hex::encode(&bytes[bytes.len() - 4..]).to_uppercase()
+    )
+}
@@ -0,0 +1,292 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, date_str, Call, DecodedCall};
+use crate::types::{Address, DescriptorCache, TokenCache, U256};
+use alloc::{format, vec::Vec};
+
+// aggregate((address,bytes)[])
+const AGGREGATE: [u8; 4] = [0x25, 0x2d, 0xba, 0x42];
+// tryAggregate(bool,(address,bytes)[])
+const TRY_AGGREGATE: [u8; 4] = [0xbc, 0xe3, 0x8b, 0xd7];
+// aggregate3((address,bool,bytes)[])
+const AGGREGATE3: [u8; 4] = [0x82, 0xad, 0x56, 0xcb];
+// aggregate3Value((address,bool,uint256,bytes)[])
+const AGGREGATE3_VALUE: [u8; 4] = [0x17, 0x4d, 0xea, 0x71];
+// multicall(bytes[])
+const MULTICALL: [u8; 4] = [0xac, 0x96, 0x50, 0xd8];
+// multicall(uint256,bytes[])
+const MULTICALL_DEADLINE: [u8; 4] = [0x5a, 0xe4, 0x01, 0xdc];
+
+// Encoding of the calls of a batch.
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    // Calls of the contract to itself, as their data, sharing the value of
+    // the batch
+    Own,
+    // Calls to other contracts, as tuples of the target, a failure flag if
+    // `flag`, a value if `value`, and the data
+    External { flag: bool, value: bool },
+}
+
+/// Decodes the batches of calls made by Multicall contracts to other
+/// contracts, or by a contract to itself.
+///
+/// The calls are decoded one by one, only unknown calls are blind signed.
+/// Batches nested in a batch are not decoded.
+pub fn decode(
+    call: &Call,
+    tokens: &TokenCache,
+    descriptors: &DescriptorCache,
+    selector: [u8; 4],
+    args: &[u8],
+) -> Option<DecodedCall> {
+    let external = |flag, value| Layout::External { flag, value };
+    let (layout, calls, deadline) = match selector {
+        AGGREGATE => {
+            let args = Args::with_dynamic(args, 1)?;
+            let layout = external(false, false);
+            (layout, calls(call, &args, 0, 1, layout)?, None)
+        }
+        TRY_AGGREGATE => {
+            let args = Args::with_dynamic(args, 2)?;
+            args.bool(0)?;
+            let layout = external(false, false);
+            (layout, calls(call, &args, 1, 2, layout)?, None)
+        }
+        AGGREGATE3 => {
+            let args = Args::with_dynamic(args, 1)?;
+            let layout = external(true, false);
+            (layout, calls(call, &args, 0, 1, layout)?, None)
+        }
+        AGGREGATE3_VALUE => {
+            let args = Args::with_dynamic(args, 1)?;
+            let layout = external(true, true);
+            (layout, calls(call, &args, 0, 1, layout)?, None)
+        }
+        MULTICALL => {
+            let args = Args::with_dynamic(args, 1)?;
+            (Layout::Own, calls(call, &args, 0, 1, Layout::Own)?, None)
+        }
+        MULTICALL_DEADLINE => {
+            let args = Args::with_dynamic(args, 2)?;
+            let calls = calls(call, &args, 1, 2, Layout::Own)?;
+            (Layout::Own, calls, Some(args.uint(0)?))
+        }
+        _ => return None,
+    };
+    if calls.is_empty() {
+        return None;
+    }
+
+    let count = calls.len();
+    // Decoded inner calls hide the recipient of the transaction, so the
+    // contract running the batch is displayed
+    let mut decoded =
+        DecodedCall::new(&format!("make {} calls", count)).address("Contract", call.to.0);
+    if let Some(deadline) = deadline {
+        decoded = decoded.text("Deadline", date_str(deadline));
+    }
+    for (i, inner) in calls.iter().enumerate() {
+        let name = format!("Call {} of {}", i + 1, count);
+        let (shows_value, data) = match super::decode(inner, tokens, descriptors, true) {
+            Some(call) => {
+                decoded = decoded.text(&name, call.operation);
+                decoded.fields.extend(call.fields);
+                decoded.blind |= call.blind;
+                decoded.warning = decoded.warning.or(call.warning);
+                (call.shows_value, &[][..])
+            }
+            None if inner.data.is_empty() => {
+                decoded = decoded
+                    .text(&name, "send CFX".into())
+                    .address("To", inner.to.0);
+                (false, inner.data)
+            }
+            None => {
+                decoded = decoded
+                    .text(&name, "unknown function".into())
+                    .address("Contract", inner.to.0);
+                (false, inner.data)
+            }
+        };
+        // The value of own calls is the one of the batch, displayed once
+        if layout != Layout::Own && !shows_value && !inner.value.is_zero() {
+            decoded = decoded.text("Amount", cfx_amount_str(inner.value)?);
+        }
+        decoded = decoded.data(data);
+    }
+    Some(decoded)
+}
+
+// Reads the calls of the array at `index`, the only dynamic argument of a
+// function taking `count` arguments
+fn calls<'a>(
+    call: &Call<'a>,
+    args: &Args<'a>,
+    index: usize,
+    count: usize,
+    layout: Layout,
+) -> Option<Vec<Call<'a>>> {
+    let mut tails = args.tails(count);
+    let (len, mut items) = tails.dynamic_array(index)?;
+    let mut calls = Vec::new();
+    for i in 0..len {
+        let inner = match layout {
+            Layout::Own => Call {
+                tx: call.tx,
+                to: call.to.clone(),
+                value: call.value,
+                data: items.bytes(i)?,
+            },
+            Layout::External { flag, value } => {
+                let head = 2 + usize::from(flag) + usize::from(value);
+                let (tuple, mut content) = items.tuple(i, head)?;
+                if flag {
+                    tuple.bool(1)?;
+                }
+                let data = content.bytes(head - 1)?;
+                items.close(content);
+                Call {
+                    tx: call.tx,
+                    to: Address(tuple.address(0)?),
+                    value: match value {
+                        true => tuple.uint(2)?,
+                        false => U256::zero(),
+                    },
+                    data,
+                }
+            }
+        };
+        calls.push(inner);
+    }
+    tails.close(items);
+    tails.end().then_some(calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::{DisplayValue, Transaction};
+
+    const ONE_CFX: [u8; 8] = [0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00];
+
+    // Encodes a byte string with its length and padding
+    fn bytes(data: &[u8]) -> Vec<u8> {
+        let mut encoded = word(&[data.len() as u8]).to_vec();
+        encoded.extend_from_slice(data);
+        encoded.resize(encoded.len().div_ceil(32) * 32, 0);
+        encoded
+    }
+
+    fn batch(to: Address, data: &[u8]) -> Option<DecodedCall> {
+        let tx = Transaction::default();
+        let call = Call {
+            tx: &tx,
+            to,
+            value: U256::zero(),
+            data,
+        };
+        let (selector, args) = data.split_at(4);
+        decode(
+            &call,
+            &TokenCache::default(),
+            &DescriptorCache::default(),
+            selector.try_into().ok()?,
+            args,
+        )
+    }
+
+    #[test]
+    fn decode_aggregate3_value() {
+        // transfer(address,uint256)
+        let transfer = [
+            &[0xa9, 0x05, 0x9c, 0xbb][..],
+            &word(&[0x10; 20]),
+            &word(&[0x05]),
+        ]
+        .concat();
+        let data = [
+            &AGGREGATE3_VALUE[..],
+            &word(&[0x20]),
+            &word(&[0x02]),
+            &word(&[0x40]),
+            &word(&[0x01, 0x40]),
+            // Token transfer
+            &word(&[0x8c; 20]),
+            &word(&[]),
+            &word(&[]),
+            &word(&[0x80]),
+            &bytes(&transfer),
+            // Unknown call with value
+            &word(&[0x8d; 20]),
+            &word(&[0x01]),
+            &word(&ONE_CFX),
+            &word(&[0x80]),
+            &bytes(&[0xde, 0xad, 0xbe, 0xef]),
+        ]
+        .concat();
+        let call = batch(Address([0x8e; 20]), &data).unwrap();
+        assert_eq!(call.operation, "make 2 calls");
+        let fields: Vec<_> = call
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.value.clone()))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("Contract", DisplayValue::Address([0x8e; 20])),
+                ("Call 1 of 2", DisplayValue::Text("send tokens".into())),
+                ("Token", DisplayValue::Address([0x8c; 20])),
+                ("Send", DisplayValue::Text("5".into())),
+                ("To", DisplayValue::Address([0x10; 20])),
+                ("Call 2 of 2", DisplayValue::Text("unknown function".into())),
+                ("Contract", DisplayValue::Address([0x8d; 20])),
+                ("Amount", DisplayValue::Text("1 CFX".into())),
+                ("Data", DisplayValue::Text("0xDEADBEEF".into())),
+            ]
+        );
+        assert!(call.blind);
+
+        // Trailing data
+        let data = [&data[..], &word(&[])].concat();
+        assert!(batch(Address([0x8e; 20]), &data).is_none());
+    }
+
+    #[test]
+    fn nested_batch_is_blind() {
+        let aggregate = [&AGGREGATE[..], &word(&[0x20]), &word(&[])].concat();
+        let data = [
+            &MULTICALL[..],
+            &word(&[0x20]),
+            &word(&[0x01]),
+            &word(&[0x20]),
+            &bytes(&aggregate),
+        ]
+        .concat();
+        let call = batch(Address([0x8c; 20]), &data).unwrap();
+        assert_eq!(call.fields[0].name, "Contract");
+        assert_eq!(call.fields[0].value, DisplayValue::Address([0x8c; 20]));
+        assert_eq!(
+            call.fields[1].value,
+            DisplayValue::Text("unknown function".into())
+        );
+        assert!(call.blind);
+    }
+}
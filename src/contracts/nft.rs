@@ -0,0 +1,169 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{DecodedCall, Warning};
+use crate::types::Address;
+use alloc::{format, string::ToString};
+
+// safeTransferFrom(address,address,uint256)
+const SAFE_TRANSFER_FROM: [u8; 4] = [0x42, 0x84, 0x2e, 0x0e];
+// safeTransferFrom(address,address,uint256,bytes)
+const SAFE_TRANSFER_FROM_DATA: [u8; 4] = [0xb8, 0x8d, 0x4f, 0xde];
+// setApprovalForAll(address,bool)
+const SET_APPROVAL_FOR_ALL: [u8; 4] = [0xa2, 0x2c, 0xb4, 0x65];
+// safeTransferFrom(address,address,uint256,uint256,bytes)
+const SAFE_TRANSFER_FROM_1155: [u8; 4] = [0xf2, 0x42, 0x43, 0x2a];
+// safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)
+const SAFE_BATCH_TRANSFER_FROM: [u8; 4] = [0x2e, 0xb2, 0xc2, 0xd6];
+
+const APPROVAL_FOR_ALL_WARNING: Warning = Warning {
+    title: "Access to all your NFTs",
+    lines: ["Operator gets", "all your NFTs"],
+    text: "The operator will be able to transfer all your tokens of this collection.",
+};
+
+/// Decodes CRC-721 / CRC-1155 token transfers and approvals.
+///
+/// The shared `transferFrom` function of CRC-721 is decoded with CRC-20.
+pub fn decode(collection: &Address, selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    let call = match selector {
+        SAFE_TRANSFER_FROM => {
+            let args = Args::new(args, 3)?;
+            DecodedCall::new("send an NFT")
+                .address("Collection", collection.0)
+                .text("Token ID", args.uint(2)?.to_string())
+                .address("From", args.address(0)?)
+                .address("To", args.address(1)?)
+        }
+        SAFE_TRANSFER_FROM_DATA => {
+            let args = Args::with_dynamic(args, 4)?;
+            DecodedCall::new("send an NFT")
+                .address("Collection", collection.0)
+                .text("Token ID", args.uint(2)?.to_string())
+                .address("From", args.address(0)?)
+                .address("To", args.address(1)?)
+                .data(args.bytes(3)?)
+        }
+        SAFE_TRANSFER_FROM_1155 => {
+            let args = Args::with_dynamic(args, 5)?;
+            DecodedCall::new("send NFTs")
+                .address("Collection", collection.0)
+                .text("Token ID", args.uint(2)?.to_string())
+                .text("Amount", args.uint(3)?.to_string())
+                .address("From", args.address(0)?)
+                .address("To", args.address(1)?)
+                .data(args.bytes(4)?)
+        }
+        SAFE_BATCH_TRANSFER_FROM => {
+            let args = Args::with_dynamic(args, 5)?;
+            let mut tails = args.tails(5);
+            let ids = tails.array(2, 1)?;
+            let amounts = tails.array(3, 1)?;
+            let data = tails.bytes(4)?;
+            if !tails.end() || ids.word_count() != amounts.word_count() {
+                return None;
+            }
+            let count = ids.word_count();
+            let mut call = DecodedCall::new("send NFTs").address("Collection", collection.0);
+            for i in 0..count {
+                call = call
+                    .text(
+                        &format!("Token ID {} of {}", i + 1, count),
+                        ids.uint(i)?.to_string(),
+                    )
+                    .text(
+                        &format!("Amount {} of {}", i + 1, count),
+                        amounts.uint(i)?.to_string(),
+                    );
+            }
+            call.address("From", args.address(0)?)
+                .address("To", args.address(1)?)
+                .data(data)
+        }
+        SET_APPROVAL_FOR_ALL => {
+            let args = Args::new(args, 2)?;
+            if args.bool(1)? {
+                DecodedCall::new("approve all NFTs")
+                    .address("Collection", collection.0)
+                    .address("Operator", args.address(0)?)
+                    .warning(&APPROVAL_FOR_ALL_WARNING)
+            } else {
+                DecodedCall::new("revoke an NFT operator")
+                    .address("Collection", collection.0)
+                    .address("Operator", args.address(0)?)
+            }
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    const COLLECTION: Address = Address([0x8c; 20]);
+
+    #[test]
+    fn decode_batch_transfer() {
+        let args = [
+            word(&[0x10; 20]),
+            word(&[0x11; 20]),
+            word(&[0xa0]),
+            word(&[0x01, 0x00]),
+            word(&[0x01, 0x60]),
+            // IDs
+            word(&[0x02]),
+            word(&[0x07]),
+            word(&[0x08]),
+            // Amounts
+            word(&[0x02]),
+            word(&[0x01]),
+            word(&[0x05]),
+            // No data
+            word(&[]),
+        ]
+        .concat();
+        let call = decode(&COLLECTION, SAFE_BATCH_TRANSFER_FROM, &args).unwrap();
+        assert_eq!(call.fields.len(), 7);
+        assert_eq!(call.fields[3].name, "Token ID 2 of 2");
+        assert_eq!(call.fields[3].value, DisplayValue::Text("8".into()));
+        assert_eq!(call.fields[4].value, DisplayValue::Text("5".into()));
+        assert!(!call.blind);
+
+        // Different numbers of IDs and amounts
+        let mut args = args;
+        args[8 * 32 + 31] = 0x01;
+        assert!(decode(&COLLECTION, SAFE_BATCH_TRANSFER_FROM, &args).is_none());
+    }
+
+    #[test]
+    fn decode_approval_for_all() {
+        let args = [word(&[0x10; 20]), word(&[0x01])].concat();
+        let call = decode(&COLLECTION, SET_APPROVAL_FOR_ALL, &args).unwrap();
+        assert!(call.warning.is_some());
+
+        let args = [word(&[0x10; 20]), word(&[])].concat();
+        let call = decode(&COLLECTION, SET_APPROVAL_FOR_ALL, &args).unwrap();
+        assert!(call.warning.is_none());
+
+        let args = [word(&[0x10; 20]), word(&[0x02])].concat();
+        assert!(decode(&COLLECTION, SET_APPROVAL_FOR_ALL, &args).is_none());
+    }
+}
@@ -0,0 +1,134 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, DecodedCall};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use crate::types::U256;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+/// Address of the ParamsControl internal contract.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x07,
+];
+
+// castVote(uint64,(uint16,uint256[3])[])
+const CAST_VOTE: [u8; 4] = [0x0b, 0xf6, 0x4c, 0x90];
+
+// Words of a vote: the topic index and the votes of the 3 options.
+const VOTE_LEN: usize = 4;
+
+// Voted parameters, by topic index.
+const TOPICS: [&str; 4] = [
+    "PoW base reward",
+    "PoS reward rate",
+    "Storage point share",
+    "Base fee share",
+];
+
+// Options of a topic, in the order of their votes.
+const OPTIONS: [&str; 3] = ["Unchanged", "Increase", "Decrease"];
+
+/// Decodes the governance votes on the parameters of the network.
+///
+/// Each topic is displayed with the votes given to its options.
+pub fn decode(selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    if selector != CAST_VOTE {
+        return None;
+    }
+    let args = Args::with_dynamic(args, 2)?;
+    let votes = args.array(1, VOTE_LEN)?;
+
+    let mut call =
+        DecodedCall::new("vote on parameters").text("Vote round", args.u64(0)?.to_string());
+    for i in 0..votes.word_count() / VOTE_LEN {
+        let topic = u16::try_from(votes.u64(i * VOTE_LEN)?).ok()?;
+        let name = match TOPICS.get(topic as usize) {
+            Some(name) => String::from(*name),
+            None => format!("Topic {}", topic),
+        };
+        let mut options = Vec::new();
+        for (j, option) in OPTIONS.iter().enumerate() {
+            let amount = votes.uint(i * VOTE_LEN + 1 + j)?;
+            if amount > U256::zero() {
+                options.push(format!("{} {}", option, cfx_amount_str(amount)?));
+            }
+        }
+        let value = if options.is_empty() {
+            "No vote".into()
+        } else {
+            options.join(", ")
+        };
+        call = call.text(&name, value);
+    }
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    const ONE_CFX: [u8; 8] = [0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00];
+
+    #[test]
+    fn decode_cast_vote() {
+        let args = [
+            word(&[0x0c]),
+            word(&[0x40]),
+            word(&[0x02]),
+            // Increase the PoW base reward
+            word(&[0x00]),
+            word(&[]),
+            word(&ONE_CFX),
+            word(&[]),
+            // Unknown topic
+            word(&[0x09]),
+            word(&[]),
+            word(&[]),
+            word(&[]),
+        ]
+        .concat();
+        let call = decode(CAST_VOTE, &args).unwrap();
+        assert_eq!(call.fields[0].value, DisplayValue::Text("12".into()));
+        assert_eq!(call.fields[1].name, "PoW base reward");
+        assert_eq!(
+            call.fields[1].value,
+            DisplayValue::Text("Increase 1 CFX".into())
+        );
+        assert_eq!(call.fields[2].name, "Topic 9");
+        assert_eq!(call.fields[2].value, DisplayValue::Text("No vote".into()));
+    }
+
+    #[test]
+    fn reject_partial_vote() {
+        let args = [
+            word(&[0x0c]),
+            word(&[0x40]),
+            word(&[0x01]),
+            word(&[0x00]),
+            word(&[]),
+            word(&ONE_CFX),
+        ]
+        .concat();
+        assert!(decode(CAST_VOTE, &args).is_none());
+    }
+}
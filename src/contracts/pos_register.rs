@@ -0,0 +1,119 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{abbreviated_hex, DecodedCall};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use alloc::{format, string::String};
+
+/// Address of the PoSRegister internal contract.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x05,
+];
+
+// CFX locked for each PoS vote.
+const CFX_PER_VOTE: u128 = 1000;
+
+// register(bytes32,uint64,bytes,bytes,bytes[2])
+const REGISTER: [u8; 4] = [0xe3, 0x35, 0xb4, 0x51];
+// increaseStake(uint64)
+const INCREASE_STAKE: [u8; 4] = [0x09, 0xfe, 0xcf, 0x7f];
+// retire(uint64)
+const RETIRE: [u8; 4] = [0xf4, 0x9d, 0x06, 0x38];
+
+/// Decodes the registration of PoS nodes and the changes of their votes.
+///
+/// Votes are displayed as the CFX they lock, the node keys in abbreviated
+/// form.
+pub fn decode(selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    let call = match selector {
+        REGISTER => {
+            // identifier, votes, BLS key, VRF key, BLS key proof
+            let args = Args::with_dynamic(args, 5)?;
+            DecodedCall::new("register a PoS node")
+                .text("Identifier", abbreviated_hex(args.word(0)?))
+                .text("Stake", votes_str(args.u64(1)?))
+                .text("BLS key", abbreviated_hex(args.bytes_at(2)?))
+                .text("VRF key", abbreviated_hex(args.bytes_at(3)?))
+        }
+        INCREASE_STAKE => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("increase PoS stake").text("Stake", votes_str(args.u64(0)?))
+        }
+        RETIRE => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("retire PoS votes").text("Retire", votes_str(args.u64(0)?))
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+fn votes_str(votes: u64) -> String {
+    format!("{} CFX ({} votes)", votes as u128 * CFX_PER_VOTE, votes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    #[test]
+    fn decode_increase_stake() {
+        let call = decode(INCREASE_STAKE, &word(&[0x03])).unwrap();
+        assert_eq!(
+            call.fields[0].value,
+            DisplayValue::Text("3000 CFX (3 votes)".into())
+        );
+        // More than 64 bits
+        assert!(decode(RETIRE, &word(&[0x01; 9])).is_none());
+    }
+
+    #[test]
+    fn decode_register() {
+        let args = [
+            [0x11; 32],
+            word(&[0x02]),
+            // BLS key
+            word(&[0xa0]),
+            // VRF key
+            word(&[0x01, 0x00]),
+            // Proof, not displayed
+            word(&[0x01, 0x60]),
+            word(&[0x30]),
+            [0xb1; 32],
+            [0xb2; 32],
+            word(&[0x21]),
+            [0xc1; 32],
+            [0xc2; 32],
+        ]
+        .concat();
+        let call = decode(REGISTER, &args).unwrap();
+        assert_eq!(
+            call.fields[0].value,
+            DisplayValue::Text("0x11111111...11111111".into())
+        );
+        assert_eq!(
+            call.fields[2].value,
+            DisplayValue::Text("0xB1B1B1B1...B2B2B2B2".into())
+        );
+        assert_eq!(
+            call.fields[3].value,
+            DisplayValue::Text("0xC1C1C1C1...C1C1C1C2".into())
+        );
+    }
+}
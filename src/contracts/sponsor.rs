@@ -0,0 +1,129 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, DecodedCall};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use alloc::format;
+
+/// Address of the SponsorWhitelistControl internal contract.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01,
+];
+
+// setSponsorForGas(address,uint256)
+const SET_SPONSOR_FOR_GAS: [u8; 4] = [0x3e, 0x3e, 0x64, 0x28];
+// setSponsorForCollateral(address)
+const SET_SPONSOR_FOR_COLLATERAL: [u8; 4] = [0xe6, 0x6c, 0x1b, 0xea];
+// addPrivilegeByAdmin(address,address[])
+const ADD_PRIVILEGE_BY_ADMIN: [u8; 4] = [0x22, 0xef, 0xfe, 0x84];
+// removePrivilegeByAdmin(address,address[])
+const REMOVE_PRIVILEGE_BY_ADMIN: [u8; 4] = [0x21, 0x7e, 0x05, 0x5b];
+
+/// Decodes the sponsorship of contracts and the management of their whitelist.
+///
+/// The CFX sent to sponsor a contract is the value of the transaction.
+pub fn decode(selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    let call = match selector {
+        SET_SPONSOR_FOR_GAS => {
+            let args = Args::new(args, 2)?;
+            DecodedCall::new("sponsor gas")
+                .address("Contract", args.address(0)?)
+                .text("Upper bound", cfx_amount_str(args.uint(1)?)?)
+        }
+        SET_SPONSOR_FOR_COLLATERAL => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("sponsor storage").address("Contract", args.address(0)?)
+        }
+        ADD_PRIVILEGE_BY_ADMIN => {
+            whitelist_call(DecodedCall::new("add to whitelist"), "Add", args)?
+        }
+        REMOVE_PRIVILEGE_BY_ADMIN => {
+            whitelist_call(DecodedCall::new("remove from whitelist"), "Remove", args)?
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+// The zero address stands for all the users
+fn whitelist_call(call: DecodedCall, name: &str, args: &[u8]) -> Option<DecodedCall> {
+    let args = Args::with_dynamic(args, 2)?;
+    let users = args.array(1, 1)?;
+    let mut call = call.address("Contract", args.address(0)?);
+    for i in 0..users.word_count() {
+        let name = match users.word_count() {
+            1 => format!("{} user", name),
+            count => format!("{} user {} of {}", name, i + 1, count),
+        };
+        let user = users.address(i)?;
+        call = if user == [0u8; ADDRRESS_BYTES_LEN] {
+            call.text(&name, "All users".into())
+        } else {
+            call.address(&name, user)
+        };
+    }
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    #[test]
+    fn decode_add_privilege() {
+        let args = [
+            word(&[0x80; 20]),
+            word(&[0x40]),
+            word(&[0x02]),
+            word(&[0x10; 20]),
+            word(&[]),
+        ]
+        .concat();
+        let call = decode(ADD_PRIVILEGE_BY_ADMIN, &args).unwrap();
+        assert_eq!(call.fields.len(), 3);
+        assert_eq!(call.fields[0].value, DisplayValue::Address([0x80; 20]));
+        assert_eq!(call.fields[1].name, "Add user 1 of 2");
+        assert_eq!(call.fields[1].value, DisplayValue::Address([0x10; 20]));
+        assert_eq!(call.fields[2].value, DisplayValue::Text("All users".into()));
+    }
+
+    #[test]
+    fn reject_hidden_data() {
+        // Array not right after the arguments
+        let args = [
+            word(&[0x80; 20]),
+            word(&[0x60]),
+            word(&[]),
+            word(&[0x01]),
+            word(&[0x10; 20]),
+        ]
+        .concat();
+        assert!(decode(ADD_PRIVILEGE_BY_ADMIN, &args).is_none());
+        // Trailing data after the array
+        let args = [
+            word(&[0x80; 20]),
+            word(&[0x40]),
+            word(&[0x01]),
+            word(&[0x10; 20]),
+            word(&[]),
+        ]
+        .concat();
+        assert!(decode(REMOVE_PRIVILEGE_BY_ADMIN, &args).is_none());
+    }
+}
@@ -0,0 +1,81 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, DecodedCall};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use alloc::string::ToString;
+
+/// Address of the Staking internal contract.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x08, 0x88, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02,
+];
+
+// deposit(uint256)
+const DEPOSIT: [u8; 4] = [0xb6, 0xb5, 0x5f, 0x25];
+// withdraw(uint256)
+const WITHDRAW: [u8; 4] = [0x2e, 0x1a, 0x7d, 0x4d];
+// voteLock(uint256,uint256)
+const VOTE_LOCK: [u8; 4] = [0x44, 0xa5, 0x1d, 0x6d];
+
+/// Decodes the staking of CFX: deposits, withdrawals and locks for voting.
+pub fn decode(selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    let call = match selector {
+        DEPOSIT => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("stake CFX").text("Stake", cfx_amount_str(args.uint(0)?)?)
+        }
+        WITHDRAW => {
+            let args = Args::new(args, 1)?;
+            DecodedCall::new("unstake CFX").text("Unstake", cfx_amount_str(args.uint(0)?)?)
+        }
+        VOTE_LOCK => {
+            let args = Args::new(args, 2)?;
+            DecodedCall::new("lock CFX")
+                .text("Lock", cfx_amount_str(args.uint(0)?)?)
+                .text("Until block", args.uint(1)?.to_string())
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::DisplayValue;
+
+    #[test]
+    fn decode_deposit() {
+        // 100 CFX
+        let args = word(&[0x05, 0x6b, 0xc7, 0x5e, 0x2d, 0x63, 0x10, 0x00, 0x00]);
+        let call = decode(DEPOSIT, &args).unwrap();
+        assert_eq!(call.fields[0].value, DisplayValue::Text("100 CFX".into()));
+    }
+
+    #[test]
+    fn decode_vote_lock() {
+        let args = [
+            word(&[0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00]),
+            word(&[0x30, 0x39]),
+        ]
+        .concat();
+        let call = decode(VOTE_LOCK, &args).unwrap();
+        assert_eq!(call.fields[0].value, DisplayValue::Text("1 CFX".into()));
+        assert_eq!(call.fields[1].value, DisplayValue::Text("12345".into()));
+    }
+}
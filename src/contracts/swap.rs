@@ -0,0 +1,246 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{cfx_amount_str, date_str, Call, DecodedCall};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use crate::types::{TokenCache, U256};
+use alloc::{format, string::ToString};
+
+// Swappi names the native currency CFX, other Uniswap V2 forks keep ETH.
+
+// swapExactCFXForTokens(uint256,address[],address,uint256)
+const SWAP_EXACT_CFX_FOR_TOKENS: [u8; 4] = [0x5c, 0x35, 0x08, 0x38];
+// swapExactETHForTokens(uint256,address[],address,uint256)
+const SWAP_EXACT_ETH_FOR_TOKENS: [u8; 4] = [0x7f, 0xf3, 0x6a, 0xb5];
+// swapExactTokensForTokens(uint256,uint256,address[],address,uint256)
+const SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = [0x38, 0xed, 0x17, 0x39];
+// swapExactTokensForCFX(uint256,uint256,address[],address,uint256)
+const SWAP_EXACT_TOKENS_FOR_CFX: [u8; 4] = [0xf6, 0x79, 0xdb, 0x95];
+// swapExactTokensForETH(uint256,uint256,address[],address,uint256)
+const SWAP_EXACT_TOKENS_FOR_ETH: [u8; 4] = [0x18, 0xcb, 0xaf, 0xe5];
+// addLiquidity(address,address,uint256,uint256,uint256,uint256,address,uint256)
+const ADD_LIQUIDITY: [u8; 4] = [0xe8, 0xe3, 0x37, 0x00];
+// addLiquidityCFX(address,uint256,uint256,uint256,address,uint256)
+const ADD_LIQUIDITY_CFX: [u8; 4] = [0x1c, 0x6a, 0xa0, 0xbb];
+// addLiquidityETH(address,uint256,uint256,uint256,address,uint256)
+const ADD_LIQUIDITY_ETH: [u8; 4] = [0xf3, 0x05, 0xd7, 0x19];
+// removeLiquidity(address,address,uint256,uint256,uint256,address,uint256)
+const REMOVE_LIQUIDITY: [u8; 4] = [0xba, 0xa2, 0xab, 0xde];
+// removeLiquidityCFX(address,uint256,uint256,uint256,address,uint256)
+const REMOVE_LIQUIDITY_CFX: [u8; 4] = [0x1b, 0x64, 0x80, 0x26];
+// removeLiquidityETH(address,uint256,uint256,uint256,address,uint256)
+const REMOVE_LIQUIDITY_ETH: [u8; 4] = [0x02, 0x75, 0x1c, 0xec];
+
+/// Decodes the swaps and liquidity changes of Swappi and other Uniswap V2
+/// style routers.
+///
+/// Token amounts are formatted with the metadata of `tokens`. Unknown tokens
+/// are displayed by address, with their amounts in their smallest unit.
+pub fn decode(
+    call: &Call,
+    tokens: &TokenCache,
+    selector: [u8; 4],
+    args: &[u8],
+) -> Option<DecodedCall> {
+    let router = Router { call, tokens };
+    let value = call.value;
+    let call = match selector {
+        SWAP_EXACT_CFX_FOR_TOKENS | SWAP_EXACT_ETH_FOR_TOKENS => {
+            let args = Args::with_dynamic(args, 4)?;
+            let path = path(&args, 1, 4)?;
+            let call = router.call("swap CFX").value("Sell", value)?;
+            let call = router.amount(call, "Buy at least", path.1, args.uint(0)?)?;
+            router.recipient(call, &args, 2, 3)?
+        }
+        SWAP_EXACT_TOKENS_FOR_TOKENS => {
+            let args = Args::with_dynamic(args, 5)?;
+            let path = path(&args, 2, 5)?;
+            let call = router.amount(router.call("swap tokens"), "Sell", path.0, args.uint(0)?)?;
+            let call = router.amount(call, "Buy at least", path.1, args.uint(1)?)?;
+            router.recipient(call, &args, 3, 4)?
+        }
+        SWAP_EXACT_TOKENS_FOR_CFX | SWAP_EXACT_TOKENS_FOR_ETH => {
+            let args = Args::with_dynamic(args, 5)?;
+            let path = path(&args, 2, 5)?;
+            let call = router.amount(router.call("swap for CFX"), "Sell", path.0, args.uint(0)?)?;
+            let call = call.text("Buy at least", cfx_amount_str(args.uint(1)?)?);
+            router.recipient(call, &args, 3, 4)?
+        }
+        ADD_LIQUIDITY => {
+            let args = Args::new(args, 8)?;
+            let (token_a, token_b) = (args.address(0)?, args.address(1)?);
+            let call = router.call("add liquidity");
+            let call = router.amount(call, "Add up to", token_a, args.uint(2)?)?;
+            let call = router.amount(call, "Add up to", token_b, args.uint(3)?)?;
+            let call = router.amount(call, "Add at least", token_a, args.uint(4)?)?;
+            let call = router.amount(call, "Add at least", token_b, args.uint(5)?)?;
+            router.recipient(call, &args, 6, 7)?
+        }
+        ADD_LIQUIDITY_CFX | ADD_LIQUIDITY_ETH => {
+            let args = Args::new(args, 6)?;
+            let token = args.address(0)?;
+            let call = router.call("add liquidity");
+            let call = router.amount(call, "Add up to", token, args.uint(1)?)?;
+            let call = call.value("Add up to", value)?;
+            let call = router.amount(call, "Add at least", token, args.uint(2)?)?;
+            let call = call.text("Add at least", cfx_amount_str(args.uint(3)?)?);
+            router.recipient(call, &args, 4, 5)?
+        }
+        REMOVE_LIQUIDITY => {
+            let args = Args::new(args, 7)?;
+            let call = router
+                .call("remove liquidity")
+                .text("Liquidity", args.uint(2)?.to_string());
+            let call = router.amount(call, "Receive at least", args.address(0)?, args.uint(3)?)?;
+            let call = router.amount(call, "Receive at least", args.address(1)?, args.uint(4)?)?;
+            router.recipient(call, &args, 5, 6)?
+        }
+        REMOVE_LIQUIDITY_CFX | REMOVE_LIQUIDITY_ETH => {
+            let args = Args::new(args, 6)?;
+            let call = router
+                .call("remove liquidity")
+                .text("Liquidity", args.uint(1)?.to_string());
+            let call = router.amount(call, "Receive at least", args.address(0)?, args.uint(2)?)?;
+            let call = call.text("Receive at least", cfx_amount_str(args.uint(3)?)?);
+            router.recipient(call, &args, 4, 5)?
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+struct Router<'a> {
+    call: &'a Call<'a>,
+    tokens: &'a TokenCache,
+}
+
+impl Router<'_> {
+    // Any contract may implement these functions, so the router is displayed
+    fn call(&self, operation: &str) -> DecodedCall {
+        DecodedCall::new(operation).address("Router", self.call.to.0)
+    }
+
+    // Amount of a token, preceded by the token address if it is unknown
+    fn amount(
+        &self,
+        call: DecodedCall,
+        name: &str,
+        token: [u8; ADDRRESS_BYTES_LEN],
+        amount: U256,
+    ) -> Option<DecodedCall> {
+        match self.tokens.get(&token, self.call.tx.chain_id) {
+            Some(info) => Some(call.text(
+                name,
+                format!("{} {}", amount.decimal_str(info.decimals)?, info.ticker),
+            )),
+            None => Some(call.address("Token", token).text(name, amount.to_string())),
+        }
+    }
+
+    fn recipient(
+        &self,
+        call: DecodedCall,
+        args: &Args,
+        to: usize,
+        deadline: usize,
+    ) -> Option<DecodedCall> {
+        Some(
+            call.address("Recipient", args.address(to)?)
+                .text("Deadline", date_str(args.uint(deadline)?)),
+        )
+    }
+}
+
+// Reads the first and last tokens of the swap path at `index`, the only
+// dynamic argument of a function taking `count` arguments
+fn path(
+    args: &Args,
+    index: usize,
+    count: usize,
+) -> Option<([u8; ADDRRESS_BYTES_LEN], [u8; ADDRRESS_BYTES_LEN])> {
+    let mut tails = args.tails(count);
+    let path = tails.array(index, 1)?;
+    if !tails.end() || path.word_count() < 2 {
+        return None;
+    }
+    Some((path.address(0)?, path.address(path.word_count() - 1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::{Address, DisplayValue, TokenInfo, Transaction};
+
+    #[test]
+    fn decode_swap_exact_tokens_for_tokens() {
+        let mut tokens = TokenCache::default();
+        tokens.add(TokenInfo {
+            address: [0xaa; 20],
+            chain_id: 1029,
+            ticker: "USDT".into(),
+            decimals: 6,
+        });
+        let tx = Transaction {
+            chain_id: 1029,
+            ..Default::default()
+        };
+        let args = [
+            word(&[0x01, 0x00]),
+            word(&[0x07]),
+            word(&[0xa0]),
+            word(&[0x10; 20]),
+            // 2024-05-01 00:00:00 UTC
+            word(&[0x66, 0x31, 0x86, 0x00]),
+            word(&[0x03]),
+            word(&[0xaa; 20]),
+            word(&[0xcc; 20]),
+            word(&[0xbb; 20]),
+        ]
+        .concat();
+        let call = Call {
+            tx: &tx,
+            to: Address([0x8c; 20]),
+            value: U256::zero(),
+            data: &[],
+        };
+        let decoded = decode(&call, &tokens, SWAP_EXACT_TOKENS_FOR_TOKENS, &args).unwrap();
+        let values: alloc::vec::Vec<_> = decoded.fields.iter().map(|f| f.value.clone()).collect();
+        assert_eq!(
+            values,
+            [
+                DisplayValue::Address([0x8c; 20]),
+                DisplayValue::Text("0.000256 USDT".into()),
+                DisplayValue::Address([0xbb; 20]),
+                DisplayValue::Text("7".into()),
+                DisplayValue::Address([0x10; 20]),
+                DisplayValue::Text("2024-05-01 00:00:00 UTC".into()),
+            ]
+        );
+
+        // Single token path
+        let mut args = args;
+        args[5 * 32 + 31] = 0x01;
+        assert!(decode(
+            &call,
+            &tokens,
+            SWAP_EXACT_TOKENS_FOR_TOKENS,
+            &args[..7 * 32]
+        )
+        .is_none());
+    }
+}
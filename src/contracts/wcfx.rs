@@ -0,0 +1,110 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use super::abi::Args;
+use super::{Call, DecodedCall};
+use crate::cfx_addr::Network;
+use crate::consts::{ADDRRESS_BYTES_LEN, EXPONENT_SMALLEST_UNIT};
+use alloc::format;
+
+/// Address of WCFX on Core mainnet, cfx:acg158kvr8zanb1bs048ryb6rtrhr283ma70vz70tx.
+pub const ADDRESS: [u8; ADDRRESS_BYTES_LEN] = [
+    0x8d, 0x7d, 0xf9, 0x31, 0x6f, 0xaa, 0x05, 0x86, 0xe1, 0x75, 0xb5, 0xe6, 0xd0, 0x3c, 0x6b, 0xda,
+    0x76, 0xe3, 0xd9, 0x50,
+];
+
+// deposit()
+const DEPOSIT: [u8; 4] = [0xd0, 0xe3, 0x0d, 0xb0];
+// withdraw(uint256)
+const WITHDRAW: [u8; 4] = [0x2e, 0x1a, 0x7d, 0x4d];
+
+/// Decodes the wrapping of CFX into WCFX and back.
+///
+/// The CFX wrapped is the value of the call. The contract is not displayed,
+/// so only the mainnet deployment is decoded.
+pub fn decode(call: &Call, selector: [u8; 4], args: &[u8]) -> Option<DecodedCall> {
+    if call.tx.is_espace()
+        || Network::from_network_id(call.tx.chain_id) != Network::Main
+        || call.to.0 != ADDRESS
+    {
+        return None;
+    }
+    let call = match selector {
+        DEPOSIT => {
+            Args::new(args, 0)?;
+            DecodedCall::new("wrap CFX").value("Wrap", call.value)?
+        }
+        WITHDRAW => {
+            let args = Args::new(args, 1)?;
+            // WCFX has the decimals of CFX
+            let amount = args.uint(0)?.decimal_str(EXPONENT_SMALLEST_UNIT as u8)?;
+            DecodedCall::new("unwrap WCFX").text("Unwrap", format!("{} WCFX", amount))
+        }
+        _ => return None,
+    };
+    Some(call)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::abi::word;
+    use crate::types::{Address, DisplayValue, Transaction, U256};
+
+    #[test]
+    fn decode_wrapping() {
+        let tx = Transaction {
+            chain_id: 1029,
+            ..Default::default()
+        };
+        let call = Call {
+            tx: &tx,
+            to: Address(ADDRESS),
+            value: U256::from(1_500_000_000_000_000_000u64),
+            data: &[],
+        };
+        let decoded = decode(&call, DEPOSIT, &[]).unwrap();
+        assert!(decoded.shows_value);
+        assert_eq!(
+            decoded.fields[0].value,
+            DisplayValue::Text("1.5 CFX".into())
+        );
+
+        let decoded = decode(
+            &call,
+            WITHDRAW,
+            &word(&[0x0d, 0xe0, 0xb6, 0xb3, 0xa7, 0x64, 0x00, 0x00]),
+        )
+        .unwrap();
+        assert!(!decoded.shows_value);
+        assert_eq!(decoded.fields[0].value, DisplayValue::Text("1 WCFX".into()));
+
+        // Other contracts
+        let other = Call {
+            to: Address([0x8c; 20]),
+            ..call
+        };
+        assert!(decode(&other, DEPOSIT, &[]).is_none());
+
+        // Other networks
+        let tx = Transaction {
+            chain_id: 1,
+            ..Default::default()
+        };
+        let call = Call { tx: &tx, ..call };
+        assert!(decode(&call, DEPOSIT, &[]).is_none());
+    }
+}
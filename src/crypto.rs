@@ -1,4 +1,8 @@
+use crate::consts::{HASH_BYTES_LEN, TOKEN_SIGNER_PUBKEY};
+use crate::AppSW;
 use k256::ecdsa::Signature;
+use ledger_device_sdk::ecc::{CurvesId, ECPublicKey};
+use ledger_device_sdk::hash::{sha2::Sha2_256, HashInit};
 
 pub fn decode_der_sig(
     der_sig: &[u8],
@@ -10,3 +14,21 @@ pub fn decode_der_sig(
     s_bytes.copy_from_slice(&sig.s().to_bytes());
     Ok(())
 }
+
+/// Checks that a record provided by the host, such as token metadata, is
+/// signed by the token signer key set at build time.
+pub fn verify_record_signature(signed: &[u8], signature: &[u8]) -> Result<(), AppSW> {
+    let mut sha256 = Sha2_256::new();
+    let mut hash = [0u8; HASH_BYTES_LEN];
+    sha256.update(signed).map_err(|_| AppSW::InternalError)?;
+    sha256
+        .finalize(&mut hash)
+        .map_err(|_| AppSW::InternalError)?;
+
+    let mut signer = ECPublicKey::<65, 'W'>::new(CurvesId::Secp256k1);
+    signer.pubkey = TOKEN_SIGNER_PUBKEY;
+    if !signer.verify((signature, signature.len() as u32), &hash) {
+        return Err(AppSW::InvalidData);
+    }
+    Ok(())
+}
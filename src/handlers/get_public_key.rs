@@ -15,24 +15,36 @@
  *  limitations under the License.
  *****************************************************************************/
 
-use crate::app_ui::address::ui_display_pk;
+use crate::app_ui::address::{ui_display_espace_pk, ui_display_pk};
 use crate::consts::{ADDRRESS_BYTES_LEN, HASH_BYTES_LEN};
-use crate::utils::Bip32Path;
+use crate::utils::{espace_mapped_address, Bip32Path};
 use crate::AppSW;
 use ledger_device_sdk::ecc::{Secp256k1, SeedDerive};
 use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
 use ledger_device_sdk::io::Comm;
 
+/// Returns the public key at the given path, and optionally the chain code.
+///
+/// With `espace`, the unmodified Keccak-derived address is displayed as an
+/// EIP-55 hex string and appended to the response. Otherwise the Core user
+/// address is displayed in base32 for the chain id following the path, and
+/// with `mapped` the eSpace address it is mapped to is displayed next to it
+/// and appended to the response.
 pub fn handler_get_public_key(
     comm: &mut Comm,
     display: bool,
     return_chain_code: bool,
+    espace: bool,
+    mapped: bool,
 ) -> Result<(), AppSW> {
     let mut chain_id: u32 = 0;
     let mut data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
 
-    // Extract chain id if display is requested (last 4 bytes are chain id)
-    if display {
+    // Extract chain id if a Core address display is requested (last 4 bytes are chain id)
+    if display && !espace {
+        if data.len() < 4 {
+            return Err(AppSW::WrongApduLength);
+        }
         chain_id = u32::from_be_bytes(data[data.len() - 4..].try_into().unwrap());
         data = &data[..data.len() - 4];
     }
@@ -43,18 +55,36 @@ pub fn handler_get_public_key(
     let pk = k.public_key().map_err(|_| AppSW::KeyDeriveFail)?;
     drop(k);
 
-    // Display address on device if requested
-    if display {
-        let mut keccak256 = Keccak256::new();
-        let mut address: [u8; 32] = [0u8; HASH_BYTES_LEN];
+    let mut keccak256 = Keccak256::new();
+    let mut address: [u8; 32] = [0u8; HASH_BYTES_LEN];
+
+    let _ = keccak256.hash(&pk.pubkey[1..], &mut address);
 
-        let _ = keccak256.hash(&pk.pubkey[1..], &mut address);
+    // eSpace addresses are the last 20 bytes of the hash, unmodified
+    let mut espace_address: [u8; ADDRRESS_BYTES_LEN] = address
+        [HASH_BYTES_LEN - ADDRRESS_BYTES_LEN..]
+        .try_into()
+        .unwrap();
 
+    if !espace {
         // Conflux user addresses start with b0001
         address[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN] &= 0x0f;
         address[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN] |= 0x10;
 
-        if !ui_display_pk(&address, chain_id)? {
+        if mapped {
+            espace_address =
+                espace_mapped_address(&address[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN..])?;
+        }
+    }
+
+    // Display address on device if requested
+    if display {
+        let approved = if espace {
+            ui_display_espace_pk(&espace_address)?
+        } else {
+            ui_display_pk(&address, chain_id, mapped.then_some(&espace_address[..]))?
+        };
+        if !approved {
             return Err(AppSW::Deny);
         }
     }
@@ -62,13 +92,17 @@ pub fn handler_get_public_key(
     comm.append(&[pk.pubkey.len() as u8]);
     comm.append(&pk.pubkey);
 
-    if !return_chain_code {
-        return Ok(());
+    if return_chain_code {
+        const CHAINCODE_LEN: u8 = 32;
+        let code = cc.unwrap();
+        comm.append(&[CHAINCODE_LEN]);
+        comm.append(&code.value);
+    }
+
+    if espace || mapped {
+        comm.append(&[ADDRRESS_BYTES_LEN as u8]);
+        comm.append(&espace_address);
     }
-    const CHAINCODE_LEN: u8 = 32;
-    let code = cc.unwrap();
-    comm.append(&[CHAINCODE_LEN]);
-    comm.append(&code.value);
 
     Ok(())
 }
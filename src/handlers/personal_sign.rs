@@ -0,0 +1,85 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::app_ui::personal_sign::ui_display_personal_msg;
+use crate::consts::{MAX_MESSAGE_LEN, PERSONAL_MESSAGE_PREFIX};
+use crate::handlers::sign_tx::{compute_signature_and_append, TxContext};
+use crate::AppSW;
+use alloc::string::ToString;
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
+use ledger_device_sdk::io::Comm;
+
+pub fn handler_personal_sign(
+    comm: &mut Comm,
+    chunk: u8,
+    more: bool,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    // Try to get data from comm
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    // First chunk, try to parse the path
+    if chunk == 0 {
+        // Reset transaction context
+        ctx.reset();
+        // This will propagate the error if the path is invalid
+        ctx.path = data.try_into()?;
+        Ok(())
+    // Next chunks, append data to the message buffer and return or
+    // display the message if it is the last chunk.
+    } else {
+        if ctx.raw_msg.len() + data.len() > MAX_MESSAGE_LEN {
+            return Err(AppSW::TxWrongLength);
+        }
+
+        // Append data to the message buffer
+        ctx.raw_msg.extend(data);
+
+        // If we expect more chunks, return
+        if more {
+            ctx.review_finished = false;
+            Ok(())
+        // Otherwise, display the message. If user approves
+        // the message, sign it. Otherwise, return a "deny" status word.
+        } else if ui_display_personal_msg(&ctx.raw_msg)? {
+            ctx.review_finished = true;
+            let message_hash = personal_message_hash(&ctx.raw_msg)?;
+            compute_signature_and_append(comm, &ctx.path, &message_hash)
+        } else {
+            ctx.review_finished = true;
+            Err(AppSW::Deny)
+        }
+    }
+}
+
+/// Computes `keccak256("\x19Conflux Signed Message:\n" || len(message) || message)`,
+/// where the length is encoded as a decimal string.
+fn personal_message_hash(message: &[u8]) -> Result<[u8; 32], AppSW> {
+    let mut keccak256 = Keccak256::new();
+    let mut message_hash: [u8; 32] = [0u8; 32];
+
+    keccak256
+        .update(PERSONAL_MESSAGE_PREFIX)
+        .map_err(|_| AppSW::TxHashFail)?;
+    keccak256
+        .update(message.len().to_string().as_bytes())
+        .map_err(|_| AppSW::TxHashFail)?;
+    keccak256.update(message).map_err(|_| AppSW::TxHashFail)?;
+    keccak256
+        .finalize(&mut message_hash)
+        .map_err(|_| AppSW::TxHashFail)?;
+
+    Ok(message_hash)
+}
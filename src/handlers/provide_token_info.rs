@@ -0,0 +1,35 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::crypto::verify_record_signature;
+use crate::handlers::sign_tx::TxContext;
+use crate::types::TokenRecord;
+use crate::AppSW;
+use ledger_device_sdk::io::Comm;
+
+/// Receives the metadata of a token, used to display its amounts in the
+/// review of the following transactions.
+///
+/// The record is only kept if it is signed by the token signer key set at
+/// build time.
+pub fn handler_provide_token_info(comm: &mut Comm, ctx: &mut TxContext) -> Result<(), AppSW> {
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    let record = TokenRecord::try_from(data)?;
+    verify_record_signature(record.signed, record.signature)?;
+
+    ctx.tokens.add(record.info);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::address_book::{parse_label, AddressBook};
+use crate::app_ui::address::{ui_display_contact, ui_display_contact_saved};
+use crate::handlers::sign_tx::TxContext;
+use crate::AppSW;
+use ledger_device_sdk::io::Comm;
+
+/// Saves the recipient of the last approved transaction in the address book,
+/// with a label typed on the host.
+///
+/// The recipient and its label are reviewed by the user before being saved.
+/// A recipient can only be saved once, right after its transaction.
+pub fn handler_save_contact(comm: &mut Comm, ctx: &mut TxContext) -> Result<(), AppSW> {
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    let label = parse_label(data)?;
+    let recipient = ctx.recipient.take().ok_or(AppSW::BadState)?;
+
+    let addr_str = recipient.address_str()?;
+    let address_book: AddressBook = Default::default();
+    let result = match ui_display_contact(&addr_str, label)? {
+        true => address_book.save(recipient, label),
+        false => Err(AppSW::Deny),
+    };
+    ui_display_contact_saved(result, ctx);
+    result
+}
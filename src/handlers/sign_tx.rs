@@ -14,25 +14,37 @@
  *  See the License for the specific language governing permissions and
  *  limitations under the License.
  *****************************************************************************/
+use crate::address_book::Recipient;
 use crate::app_ui::sign::ui_display_tx;
-use crate::consts::MAX_TRANSACTION_LEN;
+use crate::contracts::decode_call;
 use crate::crypto::decode_der_sig;
-use crate::types::Transaction;
+use crate::types::{Cip23Context, DescriptorCache, NameCache, TokenCache, TxParser};
 use crate::utils::Bip32Path;
 use crate::AppSW;
 use alloc::vec::Vec;
 use ledger_device_sdk::ecc::{Secp256k1, SeedDerive};
 use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
 use ledger_device_sdk::io::Comm;
-use rlp_decoder::decode;
 
 #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
 use ledger_device_sdk::nbgl::NbglHomeAndSettings;
 
 pub struct TxContext {
-    pub raw_tx: Vec<u8>,
+    pub raw_msg: Vec<u8>,
+    pub tx_parser: TxParser,
+    pub tx_hasher: Keccak256,
     pub path: Bip32Path,
     pub review_finished: bool,
+    pub cip23: Cip23Context,
+    /// Token metadata provided by the host, kept across transactions.
+    pub tokens: TokenCache,
+    /// Call descriptors provided by the host, kept across transactions.
+    pub descriptors: DescriptorCache,
+    /// Trusted names provided by the host, kept across transactions.
+    pub names: NameCache,
+    /// Recipient of the last approved transaction, which the host may ask
+    /// to save in the address book.
+    pub recipient: Option<Recipient>,
     #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
     pub home: NbglHomeAndSettings,
 }
@@ -42,9 +54,16 @@ impl TxContext {
     // Constructor
     pub fn new() -> TxContext {
         TxContext {
-            raw_tx: Vec::new(),
+            raw_msg: Vec::new(),
+            tx_parser: Default::default(),
+            tx_hasher: Keccak256::new(),
             path: Default::default(),
             review_finished: false,
+            cip23: Default::default(),
+            tokens: Default::default(),
+            descriptors: Default::default(),
+            names: Default::default(),
+            recipient: None,
             #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
             home: Default::default(),
         }
@@ -56,16 +75,22 @@ impl TxContext {
     }
     // Implement reset for TxInfo
     pub fn reset(&mut self) {
-        self.raw_tx.clear();
+        self.raw_msg.clear();
+        self.tx_parser = Default::default();
+        self.tx_hasher.reset();
         self.path = Default::default();
         self.review_finished = false;
+        self.cip23 = Default::default();
+        self.recipient = None;
     }
 }
 
+/// Signs a Core transaction, or an eSpace transaction if `espace` is set.
 pub fn handler_sign_tx(
     comm: &mut Comm,
     chunk: u8,
     more: bool,
+    espace: bool,
     ctx: &mut TxContext,
 ) -> Result<(), AppSW> {
     // Try to get data from comm
@@ -74,18 +99,19 @@ pub fn handler_sign_tx(
     if chunk == 0 {
         // Reset transaction context
         ctx.reset();
+        ctx.tx_parser = TxParser::new(espace);
         // This will propagate the error if the path is invalid
         ctx.path = data.try_into()?;
         Ok(())
-    // Next chunks, append data to raw_tx and return or parse
-    // the transaction if it is the last chunk.
+    // Next chunks, hash and parse data as it comes and return or
+    // display the transaction if it is the last chunk.
     } else {
-        if ctx.raw_tx.len() + data.len() > MAX_TRANSACTION_LEN {
-            return Err(AppSW::TxWrongLength);
+        // All chunks of a transaction must be sent with the same instruction
+        if ctx.tx_parser.is_espace() != espace {
+            return Err(AppSW::BadState);
         }
-
-        // Append data to raw_tx
-        ctx.raw_tx.extend(data);
+        ctx.tx_hasher.update(data).map_err(|_| AppSW::TxHashFail)?;
+        ctx.tx_parser.feed(data)?;
 
         // If we expect more chunks, return
         if more {
@@ -93,15 +119,35 @@ pub fn handler_sign_tx(
             Ok(())
         // Otherwise, try to parse the transaction
         } else {
-            // Try to deserialize the transaction
-            let tx: Transaction =
-                decode(ctx.raw_tx.as_slice()).map_err(|_| AppSW::TxParsingFail)?;
+            // Try to build the transaction from the parsed fields
+            let tx = ctx.tx_parser.finish()?;
+            // Known contract calls are reviewed field by field instead of as raw data
+            let call = decode_call(&tx, &ctx.tokens, &ctx.descriptors);
             // Display transaction. If user approves
             // the transaction, sign it. Otherwise,
             // return a "deny" status word.
-            if ui_display_tx(&tx, ctx)? {
+            if ui_display_tx(&tx, call.as_ref(), ctx)? {
                 ctx.review_finished = true;
-                compute_signature_and_append(comm, ctx)
+                // The recipient of decoded calls is the one displayed, e.g.
+                // the receiver of a token transfer rather than the token
+                let recipient = match &call {
+                    Some(call) => call.recipient(tx.is_espace()),
+                    None => tx.to.as_ref().map(|to| to.0),
+                };
+                ctx.recipient = recipient.map(|address| Recipient {
+                    address,
+                    chain_id: tx.chain_id,
+                    espace: tx.is_espace(),
+                });
+                let mut message_hash: [u8; 32] = [0u8; 32];
+                ctx.tx_hasher
+                    .finalize(&mut message_hash)
+                    .map_err(|_| AppSW::TxHashFail)?;
+                let (parity, r, s) = compute_signature(&ctx.path, &message_hash)?;
+                comm.append(&[tx.signature_v(parity)]);
+                comm.append(&r);
+                comm.append(&s);
+                Ok(())
             } else {
                 ctx.review_finished = true;
                 Err(AppSW::Deny)
@@ -110,14 +156,27 @@ pub fn handler_sign_tx(
     }
 }
 
-fn compute_signature_and_append(comm: &mut Comm, ctx: &mut TxContext) -> Result<(), AppSW> {
-    let mut keccak256 = Keccak256::new();
-    let mut message_hash: [u8; 32] = [0u8; 32];
+/// Signs a 32-byte hash with the key at `path` and appends `v || r || s` to the response.
+pub fn compute_signature_and_append(
+    comm: &mut Comm,
+    path: &Bip32Path,
+    message_hash: &[u8; 32],
+) -> Result<(), AppSW> {
+    let (parity, r, s) = compute_signature(path, message_hash)?;
 
-    let _ = keccak256.hash(&ctx.raw_tx, &mut message_hash);
+    comm.append(&[parity]);
+    comm.append(&r);
+    comm.append(&s);
+    Ok(())
+}
 
-    let (sig, siglen, parity) = Secp256k1::derive_from_path(ctx.path.as_ref())
-        .deterministic_sign(&message_hash)
+/// Signs a 32-byte hash with the key at `path` and returns the y parity, r and s.
+fn compute_signature(
+    path: &Bip32Path,
+    message_hash: &[u8; 32],
+) -> Result<(u8, [u8; 32], [u8; 32]), AppSW> {
+    let (sig, siglen, parity) = Secp256k1::derive_from_path(path.as_ref())
+        .deterministic_sign(message_hash)
         .map_err(|_| AppSW::TxSignFail)?;
 
     let mut r: [u8; 32] = [0u8; 32];
@@ -125,8 +184,5 @@ fn compute_signature_and_append(comm: &mut Comm, ctx: &mut TxContext) -> Result<
 
     decode_der_sig(&sig[..siglen as usize], &mut r, &mut s).map_err(|_| AppSW::TxSignFail)?;
 
-    comm.append(&[parity as u8]);
-    comm.append(&r);
-    comm.append(&s);
-    Ok(())
+    Ok((parity as u8, r, s))
 }
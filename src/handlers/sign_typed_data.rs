@@ -0,0 +1,159 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+use crate::app_ui::typed_data::{ui_display_typed_data, ui_display_typed_data_hashes};
+use crate::consts::HASH_BYTES_LEN;
+use crate::handlers::sign_tx::{compute_signature_and_append, TxContext};
+use crate::AppSW;
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
+use ledger_device_sdk::io::Comm;
+
+/// Prefix of CIP-23 typed data signing payloads, as in EIP-712.
+const CIP23_PREFIX: [u8; 2] = [0x19, 0x01];
+
+/// Signs CIP-23 typed data.
+///
+/// In hashed mode, the APDU data is the BIP32 path followed by the 32-byte domain
+/// separator hash and the 32-byte `hashStruct(message)`. Otherwise the APDU data is
+/// the BIP32 path only, and the domain and message previously streamed with
+/// [`handler_cip23_struct_def`] and [`handler_cip23_struct_impl`] are reviewed
+/// field by field.
+pub fn handler_sign_typed_data(
+    comm: &mut Comm,
+    hashed: bool,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    if !hashed {
+        return sign_streamed_typed_data(comm, data, ctx);
+    }
+    ctx.reset();
+
+    // Split the path from the two hashes
+    let path_len = 1 + *data.first().ok_or(AppSW::WrongApduLength)? as usize * 4;
+    if data.len() != path_len + 2 * HASH_BYTES_LEN {
+        return Err(AppSW::WrongApduLength);
+    }
+    ctx.path = data[..path_len].try_into()?;
+
+    let domain_hash: [u8; HASH_BYTES_LEN] = data[path_len..path_len + HASH_BYTES_LEN]
+        .try_into()
+        .unwrap();
+    let message_hash: [u8; HASH_BYTES_LEN] = data[path_len + HASH_BYTES_LEN..].try_into().unwrap();
+
+    let approved = ui_display_typed_data_hashes(&domain_hash, &message_hash, ctx)?;
+    ctx.review_finished = true;
+    if !approved {
+        return Err(AppSW::Deny);
+    }
+
+    let hash = typed_data_hash(&domain_hash, &message_hash)?;
+    compute_signature_and_append(comm, &ctx.path, &hash)
+}
+
+fn sign_streamed_typed_data(
+    comm: &mut Comm,
+    data: &[u8],
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    // The streamed typed data is consumed by this signing request
+    let cip23 = core::mem::take(&mut ctx.cip23);
+    ctx.reset();
+    ctx.path = data.try_into()?;
+
+    let (domain_hash, message_hash) = match (cip23.domain_hash, cip23.message_hash) {
+        (Some(domain_hash), Some(message_hash)) => (domain_hash, message_hash),
+        _ => return Err(AppSW::BadState),
+    };
+
+    let approved = ui_display_typed_data(&cip23, ctx)?;
+    ctx.review_finished = true;
+    if !approved {
+        return Err(AppSW::Deny);
+    }
+
+    let hash = typed_data_hash(&domain_hash, &message_hash)?;
+    compute_signature_and_append(comm, &ctx.path, &hash)
+}
+
+/// Receives a CIP-23 struct definition: either the name of a new struct, or
+/// the definition of one of its fields.
+pub fn handler_cip23_struct_def(
+    comm: &mut Comm,
+    field: bool,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    if field {
+        ctx.cip23.add_field(data)
+    } else {
+        ctx.cip23.add_struct(data)
+    }
+}
+
+/// Receives the values of a CIP-23 struct, field by field in depth-first order.
+pub fn handler_cip23_struct_impl(
+    comm: &mut Comm,
+    kind: Cip23Value,
+    ctx: &mut TxContext,
+) -> Result<(), AppSW> {
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+    let res = match kind {
+        Cip23Value::Root => ctx.cip23.start_root(data),
+        Cip23Value::ArraySize => ctx.cip23.set_array_size(data),
+        Cip23Value::Field { more } => ctx.cip23.add_value(data, more),
+    };
+    // Any error aborts the whole flow
+    if res.is_err() {
+        ctx.cip23 = Default::default();
+    }
+    res
+}
+
+/// Kind of a CIP-23 struct implementation APDU.
+#[derive(Clone, Copy)]
+pub enum Cip23Value {
+    /// Name of the root struct (`CIP23Domain` or the primary type).
+    Root,
+    /// Size of the array expected next.
+    ArraySize,
+    /// Chunk of the value of the field expected next.
+    Field { more: bool },
+}
+
+/// Computes `keccak256(0x19 0x01 || domainSeparator || hashStruct(message))`.
+pub fn typed_data_hash(
+    domain_hash: &[u8; HASH_BYTES_LEN],
+    message_hash: &[u8; HASH_BYTES_LEN],
+) -> Result<[u8; HASH_BYTES_LEN], AppSW> {
+    let mut keccak256 = Keccak256::new();
+    let mut hash = [0u8; HASH_BYTES_LEN];
+
+    keccak256
+        .update(&CIP23_PREFIX)
+        .map_err(|_| AppSW::TxHashFail)?;
+    keccak256
+        .update(domain_hash)
+        .map_err(|_| AppSW::TxHashFail)?;
+    keccak256
+        .update(message_hash)
+        .map_err(|_| AppSW::TxHashFail)?;
+    keccak256
+        .finalize(&mut hash)
+        .map_err(|_| AppSW::TxHashFail)?;
+
+    Ok(hash)
+}
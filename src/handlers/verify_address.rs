@@ -0,0 +1,75 @@
+/*****************************************************************************
+ *   Ledger App Conflux Rust.
+ *   (c) 2023 Conflux Foundation.
+ *
+ *  Licensed under the Apache License, Version 2.0 (the "License");
+ *  you may not use this file except in compliance with the License.
+ *  You may obtain a copy of the License at
+ *
+ *      http://www.apache.org/licenses/LICENSE-2.0
+ *
+ *  Unless required by applicable law or agreed to in writing, software
+ *  distributed under the License is distributed on an "AS IS" BASIS,
+ *  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ *  See the License for the specific language governing permissions and
+ *  limitations under the License.
+ *****************************************************************************/
+
+use crate::app_ui::address::{ui_display_address_mismatch, ui_display_verified_address};
+use crate::cfx_addr::{cfx_addr_decode, cfx_addr_encode, AddressType};
+use crate::consts::{ADDRRESS_BYTES_LEN, HASH_BYTES_LEN};
+use crate::handlers::sign_tx::TxContext;
+use crate::utils::Bip32Path;
+use crate::AppSW;
+use ledger_device_sdk::ecc::{Secp256k1, SeedDerive};
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
+use ledger_device_sdk::io::Comm;
+
+/// Checks that a base32 address belongs to the given path.
+///
+/// The APDU data is the BIP32 path followed by the address string. The Core
+/// user address of the path is derived as in `handler_get_public_key` and
+/// compared with the decoded string: the address is displayed for the user to
+/// confirm if they match, and a mismatch error is displayed otherwise.
+pub fn handler_verify_address(comm: &mut Comm, ctx: &mut TxContext) -> Result<(), AppSW> {
+    let data = comm.get_data().map_err(|_| AppSW::WrongApduLength)?;
+
+    // Split the path from the address string
+    let path_len = 1 + *data.first().ok_or(AppSW::WrongApduLength)? as usize * 4;
+    if data.len() <= path_len {
+        return Err(AppSW::WrongApduLength);
+    }
+    let path: Bip32Path = data[..path_len].try_into()?;
+    let addr_str = core::str::from_utf8(&data[path_len..]).map_err(|_| AppSW::InvalidData)?;
+    let decoded = cfx_addr_decode(addr_str).map_err(|_e| AppSW::InvalidData)?;
+    // Only user addresses are derived from a key
+    if decoded.address_type != AddressType::User {
+        return Err(AppSW::InvalidData);
+    }
+
+    let pk = Secp256k1::derive_from_path(path.as_ref())
+        .public_key()
+        .map_err(|_| AppSW::KeyDeriveFail)?;
+
+    let mut keccak256 = Keccak256::new();
+    let mut address: [u8; 32] = [0u8; HASH_BYTES_LEN];
+
+    let _ = keccak256.hash(&pk.pubkey[1..], &mut address);
+
+    // Conflux user addresses start with b0001
+    address[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN] &= 0x0f;
+    address[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN] |= 0x10;
+
+    if decoded.raw[..] != address[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN..] {
+        ui_display_address_mismatch(ctx);
+        return Err(AppSW::AddressMismatch);
+    }
+
+    // Display the canonical form of the verified address
+    let cfx_addr =
+        cfx_addr_encode(&decoded.raw, decoded.network).map_err(|_e| AppSW::AddrDisplayFail)?;
+    if !ui_display_verified_address(&cfx_addr)? {
+        return Err(AppSW::Deny);
+    }
+    Ok(())
+}
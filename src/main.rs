@@ -22,29 +22,50 @@ mod utils;
 mod app_ui {
     pub mod address;
     pub mod menu;
+    pub mod personal_sign;
     pub mod sign;
+    pub mod typed_data;
 }
 mod handlers {
     pub mod get_public_key;
     pub mod get_version;
+    pub mod personal_sign;
+    pub mod provide_call_descriptor;
+    pub mod provide_token_info;
+    pub mod provide_trusted_name;
+    pub mod save_contact;
     pub mod sign_tx;
+    pub mod sign_typed_data;
+    pub mod verify_address;
 }
 mod cfx_addr;
 mod consts;
+mod contracts;
 mod crypto;
 mod types;
 
+mod address_book;
 mod settings;
 
+#[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
+use app_ui::menu::ui_address_book_settings;
 use app_ui::menu::ui_menu_main;
 use handlers::{
     get_public_key::handler_get_public_key,
     get_version::handler_get_version,
+    personal_sign::handler_personal_sign,
+    provide_call_descriptor::handler_provide_call_descriptor,
+    provide_token_info::handler_provide_token_info,
+    provide_trusted_name::handler_provide_trusted_name,
+    save_contact::handler_save_contact,
     sign_tx::{handler_sign_tx, TxContext},
+    sign_typed_data::{
+        handler_cip23_struct_def, handler_cip23_struct_impl, handler_sign_typed_data, Cip23Value,
+    },
+    verify_address::handler_verify_address,
 };
 use ledger_device_sdk::io::{ApduHeader, Comm, Reply, StatusWords};
 
-#[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
 use ledger_device_sdk::io::Event;
 
 ledger_device_sdk::set_panic!(ledger_device_sdk::exiting_panic);
@@ -61,8 +82,32 @@ const P2_SIGN_TX_LAST: u8 = 0x00;
 const P2_SIGN_TX_MORE: u8 = 0x80;
 // P1 for first APDU number.
 const P1_SIGN_TX_START: u8 = 0x00;
-// P1 for maximum APDU number.
-const P1_SIGN_TX_MAX: u8 = 0x03;
+// P1 for maximum APDU number of a personal message.
+const P1_PERSONAL_SIGN_MAX: u8 = 0x03;
+
+// P2 flag to return the chain code with the public key.
+const P2_PUBKEY_CHAIN_CODE: u8 = 0x01;
+// P2 flag for the eSpace address of the public key.
+const P2_PUBKEY_ESPACE: u8 = 0x02;
+// P2 flag for the eSpace address the Core address is mapped to.
+const P2_PUBKEY_MAPPED: u8 = 0x04;
+
+// P2 for CIP-23 signing from the domain and message hashes.
+const P2_CIP23_HASHED: u8 = 0x00;
+// P2 for CIP-23 signing from the streamed domain and message.
+const P2_CIP23_FULL: u8 = 0x01;
+// P2 for a CIP-23 struct name.
+const P2_CIP23_STRUCT_NAME: u8 = 0x00;
+// P2 for a CIP-23 root struct name.
+const P2_CIP23_ROOT: u8 = 0x00;
+// P2 for a CIP-23 array size.
+const P2_CIP23_ARRAY: u8 = 0x0F;
+// P2 for a CIP-23 struct field.
+const P2_CIP23_FIELD: u8 = 0xFF;
+// P1 for a complete CIP-23 field value.
+const P1_CIP23_COMPLETE: u8 = 0x00;
+// P1 for a partial CIP-23 field value, more chunks follow.
+const P1_CIP23_PARTIAL: u8 = 0x01;
 
 // Application status words.
 #[repr(u16)]
@@ -82,6 +127,8 @@ pub enum AppSW {
     TxSignFail = 0xB008,
     KeyDeriveFail = 0xB009,
     VersionParsingFail = 0xB00A,
+    AddressMismatch = 0xB00B,
+    AddressBookFull = 0xB00C,
     WrongApduLength = StatusWords::BadLen as u16,
     Ok = 0x9000,
     //
@@ -109,11 +156,32 @@ pub enum Instruction {
     GetPubkey {
         display: bool,
         return_chain_code: bool,
+        espace: bool,
+        mapped: bool,
     },
     SignTx {
         chunk: u8,
         more: bool,
+        espace: bool,
+    },
+    PersonalSign {
+        chunk: u8,
+        more: bool,
+    },
+    SignTypedData {
+        hashed: bool,
+    },
+    Cip23StructDef {
+        field: bool,
     },
+    Cip23StructImpl {
+        kind: Cip23Value,
+    },
+    VerifyAddress,
+    ProvideTokenInfo,
+    ProvideCallDescriptor,
+    ProvideTrustedName,
+    SaveContact,
 }
 
 impl TryFrom<ApduHeader> for Instruction {
@@ -133,19 +201,61 @@ impl TryFrom<ApduHeader> for Instruction {
     fn try_from(value: ApduHeader) -> Result<Self, Self::Error> {
         match (value.ins, value.p1, value.p2) {
             (1, 0, 0) => Ok(Instruction::GetVersion),
-            (2, 0 | 1, 0 | 1) => Ok(Instruction::GetPubkey {
-                display: value.p1 != 0,
-                return_chain_code: value.p2 != 0,
+            // A mapped address only exists for Core addresses
+            (2, 0 | 1, p2)
+                if p2 & !(P2_PUBKEY_CHAIN_CODE | P2_PUBKEY_ESPACE | P2_PUBKEY_MAPPED) == 0
+                    && p2 & (P2_PUBKEY_ESPACE | P2_PUBKEY_MAPPED)
+                        != (P2_PUBKEY_ESPACE | P2_PUBKEY_MAPPED) =>
+            {
+                Ok(Instruction::GetPubkey {
+                    display: value.p1 != 0,
+                    return_chain_code: p2 & P2_PUBKEY_CHAIN_CODE != 0,
+                    espace: p2 & P2_PUBKEY_ESPACE != 0,
+                    mapped: p2 & P2_PUBKEY_MAPPED != 0,
+                })
+            }
+            // Transactions are hashed as they are received, so the number of
+            // chunks is not bounded and their index may wrap around.
+            // INS 3 signs Core transactions, INS 9 eSpace transactions.
+            (3 | 9, P1_SIGN_TX_START, P2_SIGN_TX_MORE)
+            | (3 | 9, 1..=0xFF, P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => Ok(Instruction::SignTx {
+                chunk: value.p1,
+                more: value.p2 == P2_SIGN_TX_MORE,
+                espace: value.ins == 9,
             }),
-            (3, P1_SIGN_TX_START, P2_SIGN_TX_MORE)
-            | (3, 1..=P1_SIGN_TX_MAX, P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => {
-                Ok(Instruction::SignTx {
+            (4, P1_SIGN_TX_START, P2_SIGN_TX_MORE)
+            | (4, 1..=P1_PERSONAL_SIGN_MAX, P2_SIGN_TX_LAST | P2_SIGN_TX_MORE) => {
+                Ok(Instruction::PersonalSign {
                     chunk: value.p1,
                     more: value.p2 == P2_SIGN_TX_MORE,
                 })
             }
             (5, 0, 0) => Ok(Instruction::GetAppName),
-            (1..=5, _, _) => Err(AppSW::WrongP1P2),
+            (6, 0, P2_CIP23_HASHED | P2_CIP23_FULL) => Ok(Instruction::SignTypedData {
+                hashed: value.p2 == P2_CIP23_HASHED,
+            }),
+            (7, 0, P2_CIP23_STRUCT_NAME | P2_CIP23_FIELD) => Ok(Instruction::Cip23StructDef {
+                field: value.p2 == P2_CIP23_FIELD,
+            }),
+            (8, 0, P2_CIP23_ROOT) => Ok(Instruction::Cip23StructImpl {
+                kind: Cip23Value::Root,
+            }),
+            (8, 0, P2_CIP23_ARRAY) => Ok(Instruction::Cip23StructImpl {
+                kind: Cip23Value::ArraySize,
+            }),
+            (8, P1_CIP23_COMPLETE | P1_CIP23_PARTIAL, P2_CIP23_FIELD) => {
+                Ok(Instruction::Cip23StructImpl {
+                    kind: Cip23Value::Field {
+                        more: value.p1 == P1_CIP23_PARTIAL,
+                    },
+                })
+            }
+            (10, 0, 0) => Ok(Instruction::VerifyAddress),
+            (11, 0, 0) => Ok(Instruction::ProvideTokenInfo),
+            (12, 0, 0) => Ok(Instruction::ProvideCallDescriptor),
+            (13, 0, 0) => Ok(Instruction::ProvideTrustedName),
+            (14, 0, 0) => Ok(Instruction::SaveContact),
+            (1..=14, _, _) => Err(AppSW::WrongP1P2),
             (_, _, _) => Err(AppSW::InsNotSupported),
         }
     }
@@ -155,15 +265,16 @@ impl TryFrom<ApduHeader> for Instruction {
 fn show_status_and_home_if_needed(ins: &Instruction, tx_ctx: &mut TxContext, status: &AppSW) {
     let (show_status, status_type) = match (ins, status) {
         (
-            Instruction::GetPubkey {
-                display: true,
-                return_chain_code: _,
-            },
+            Instruction::GetPubkey { display: true, .. } | Instruction::VerifyAddress,
             AppSW::Deny | AppSW::Ok,
         ) => (true, StatusType::Address),
         (Instruction::SignTx { .. }, AppSW::Deny | AppSW::Ok) if tx_ctx.finished() => {
             (true, StatusType::Transaction)
         }
+        (
+            Instruction::PersonalSign { .. } | Instruction::SignTypedData { .. },
+            AppSW::Deny | AppSW::Ok,
+        ) if tx_ctx.finished() => (true, StatusType::Message),
         (_, _) => (false, StatusType::Transaction),
     };
 
@@ -198,7 +309,15 @@ extern "C" fn sample_main() {
 
     loop {
         #[cfg(any(target_os = "stax", target_os = "flex", target_os = "apex_p"))]
-        let ins: Instruction = comm.next_command();
+        let ins: Instruction = match comm.next_event() {
+            Event::Command(ins) => ins,
+            // Settings switches are toggled by touch events
+            Event::TouchEvent => {
+                ui_address_book_settings(&mut tx_ctx.home);
+                continue;
+            }
+            _ => continue,
+        };
 
         #[cfg(any(target_os = "nanosplus", target_os = "nanox"))]
         let ins = if let Event::Command(ins) = ui_menu_main(&mut comm) {
@@ -232,7 +351,24 @@ fn handle_apdu(comm: &mut Comm, ins: &Instruction, ctx: &mut TxContext) -> Resul
         Instruction::GetPubkey {
             display,
             return_chain_code,
-        } => handler_get_public_key(comm, *display, *return_chain_code),
-        Instruction::SignTx { chunk, more } => handler_sign_tx(comm, *chunk, *more, ctx),
+            espace,
+            mapped,
+        } => handler_get_public_key(comm, *display, *return_chain_code, *espace, *mapped),
+        Instruction::SignTx {
+            chunk,
+            more,
+            espace,
+        } => handler_sign_tx(comm, *chunk, *more, *espace, ctx),
+        Instruction::PersonalSign { chunk, more } => {
+            handler_personal_sign(comm, *chunk, *more, ctx)
+        }
+        Instruction::SignTypedData { hashed } => handler_sign_typed_data(comm, *hashed, ctx),
+        Instruction::Cip23StructDef { field } => handler_cip23_struct_def(comm, *field, ctx),
+        Instruction::Cip23StructImpl { kind } => handler_cip23_struct_impl(comm, *kind, ctx),
+        Instruction::VerifyAddress => handler_verify_address(comm, ctx),
+        Instruction::ProvideTokenInfo => handler_provide_token_info(comm, ctx),
+        Instruction::ProvideCallDescriptor => handler_provide_call_descriptor(comm, ctx),
+        Instruction::ProvideTrustedName => handler_provide_trusted_name(comm, ctx),
+        Instruction::SaveContact => handler_save_contact(comm, ctx),
     }
 }
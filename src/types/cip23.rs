@@ -0,0 +1,638 @@
+use super::{DisplayField, DisplayValue, U256};
+use crate::cfx_addr::Network;
+use crate::consts::{ADDRRESS_BYTES_LEN, HASH_BYTES_LEN};
+use crate::AppSW;
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
+
+/// Name of the CIP-23 domain struct.
+pub const CIP23_DOMAIN_TYPE: &str = "CIP23Domain";
+
+// Field type descriptor byte: array flag, size flag and type id.
+const TYPE_ARRAY_FLAG: u8 = 0x80;
+const TYPE_SIZE_FLAG: u8 = 0x40;
+const TYPE_ID_MASK: u8 = 0x0f;
+
+const TYPE_ID_CUSTOM: u8 = 0;
+const TYPE_ID_INT: u8 = 1;
+const TYPE_ID_UINT: u8 = 2;
+const TYPE_ID_ADDRESS: u8 = 3;
+const TYPE_ID_BOOL: u8 = 4;
+const TYPE_ID_STRING: u8 = 5;
+const TYPE_ID_FIXED_BYTES: u8 = 6;
+const TYPE_ID_DYNAMIC_BYTES: u8 = 7;
+
+const ARRAY_LEVEL_DYNAMIC: u8 = 0x00;
+const ARRAY_LEVEL_FIXED: u8 = 0x01;
+
+/// Maximum nesting of structs and arrays while streaming values.
+const MAX_DEPTH: usize = 8;
+
+/// Maximum length of a single streamed field value (bytes).
+const MAX_VALUE_LEN: usize = 1024;
+
+/// Maximum number of struct definitions, including `CIP23Domain`.
+const MAX_STRUCTS: usize = 16;
+
+/// Maximum number of fields of a struct definition.
+const MAX_FIELDS: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BaseType {
+    Struct(String),
+    /// Signed integer, size in bytes.
+    Int(u8),
+    /// Unsigned integer, size in bytes.
+    Uint(u8),
+    Address,
+    Bool,
+    String,
+    /// Fixed size byte array, size in bytes.
+    FixedBytes(u8),
+    Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldType {
+    pub base: BaseType,
+    /// Array levels in declaration order, `None` for dynamic levels.
+    /// `uint8[3][]` is a dynamic array of `uint8[3]`.
+    pub levels: Vec<Option<u8>>,
+}
+
+impl FieldType {
+    /// Type name as used in `encodeType`, e.g. `uint256` or `Person[]`.
+    pub fn type_name(&self) -> String {
+        let mut name = match &self.base {
+            BaseType::Struct(name) => name.clone(),
+            BaseType::Int(size) => format!("int{}", *size as usize * 8),
+            BaseType::Uint(size) => format!("uint{}", *size as usize * 8),
+            BaseType::Address => "address".into(),
+            BaseType::Bool => "bool".into(),
+            BaseType::String => "string".into(),
+            BaseType::FixedBytes(size) => format!("bytes{}", size),
+            BaseType::Bytes => "bytes".into(),
+        };
+        for level in self.levels.iter() {
+            match level {
+                Some(size) => name.push_str(&format!("[{}]", size)),
+                None => name.push_str("[]"),
+            }
+        }
+        name
+    }
+
+    // Type of the elements of this array type (outermost level removed).
+    fn element_type(&self) -> FieldType {
+        FieldType {
+            base: self.base.clone(),
+            levels: self.levels[..self.levels.len() - 1].to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDef {
+    pub ty: FieldType,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructDef {
+    pub name: String,
+    pub fields: Vec<FieldDef>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Root {
+    Domain,
+    Message,
+}
+
+enum Frame {
+    Struct {
+        index: usize,
+        field: usize,
+        /// Path of the struct from the root, prefixing its member names.
+        path: String,
+        hasher: Keccak256,
+    },
+    Array {
+        ty: FieldType,
+        name: String,
+        remaining: u8,
+        hasher: Keccak256,
+    },
+}
+
+impl Frame {
+    fn update(&mut self, word: &[u8]) -> Result<(), AppSW> {
+        let hasher = match self {
+            Frame::Struct { hasher, .. } | Frame::Array { hasher, .. } => hasher,
+        };
+        hasher.update(word).map_err(|_| AppSW::TxHashFail)
+    }
+
+    fn advance(&mut self) {
+        match self {
+            Frame::Struct { field, .. } => *field += 1,
+            Frame::Array { remaining, .. } => *remaining -= 1,
+        }
+    }
+
+    fn finalize(self) -> Result<[u8; HASH_BYTES_LEN], AppSW> {
+        let mut hasher = match self {
+            Frame::Struct { hasher, .. } | Frame::Array { hasher, .. } => hasher,
+        };
+        let mut hash = [0u8; HASH_BYTES_LEN];
+        hasher.finalize(&mut hash).map_err(|_| AppSW::TxHashFail)?;
+        Ok(hash)
+    }
+}
+
+/// State of a CIP-23 clear-signing flow.
+///
+/// The host first sends every struct definition, then streams the values of
+/// the `CIP23Domain` struct followed by the values of the primary type, field by
+/// field in depth-first order. `hashStruct` is computed on the fly and every
+/// atomic value is kept for display.
+#[derive(Default)]
+pub struct Cip23Context {
+    structs: Vec<StructDef>,
+    frames: Vec<Frame>,
+    root: Option<Root>,
+    value: Vec<u8>,
+    value_len: Option<usize>,
+    pub chain_id: Option<u64>,
+    pub domain_hash: Option<[u8; HASH_BYTES_LEN]>,
+    pub message_hash: Option<[u8; HASH_BYTES_LEN]>,
+    pub fields: Vec<DisplayField>,
+}
+
+impl Cip23Context {
+    /// Starts a new struct definition.
+    pub fn add_struct(&mut self, name: &[u8]) -> Result<(), AppSW> {
+        // A definition after values were streamed starts a new flow
+        if self.root.is_some() || self.domain_hash.is_some() {
+            *self = Default::default();
+        }
+        let name = parse_name(name)?;
+        if self.structs.len() >= MAX_STRUCTS || self.structs.iter().any(|s| s.name == name) {
+            return Err(AppSW::InvalidData);
+        }
+        self.structs.push(StructDef {
+            name,
+            fields: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// Adds a field to the last defined struct.
+    pub fn add_field(&mut self, data: &[u8]) -> Result<(), AppSW> {
+        if self.root.is_some() || self.domain_hash.is_some() {
+            return Err(AppSW::BadState);
+        }
+        let field = parse_field(data)?;
+        let current = self.structs.last_mut().ok_or(AppSW::BadState)?;
+        if current.fields.len() >= MAX_FIELDS || current.fields.iter().any(|f| f.name == field.name)
+        {
+            return Err(AppSW::InvalidData);
+        }
+        current.fields.push(field);
+        Ok(())
+    }
+
+    /// Starts streaming the values of the domain or of the primary type.
+    pub fn start_root(&mut self, name: &[u8]) -> Result<(), AppSW> {
+        if self.root.is_some() || self.message_hash.is_some() {
+            return Err(AppSW::BadState);
+        }
+        let name = core::str::from_utf8(name).map_err(|_| AppSW::InvalidData)?;
+        let root = if name == CIP23_DOMAIN_TYPE {
+            if self.domain_hash.is_some() {
+                return Err(AppSW::BadState);
+            }
+            Root::Domain
+        } else {
+            // The domain comes first, its chain id is needed to display addresses
+            if self.domain_hash.is_none() {
+                return Err(AppSW::BadState);
+            }
+            Root::Message
+        };
+        let index = self.struct_index(name)?;
+        self.root = Some(root);
+        self.push_struct(index, String::new())?;
+        self.settle()
+    }
+
+    /// Sets the size of the array expected next.
+    pub fn set_array_size(&mut self, data: &[u8]) -> Result<(), AppSW> {
+        let size = match data {
+            [size] => *size,
+            _ => return Err(AppSW::WrongDataLength),
+        };
+        if self.value_len.is_some() {
+            return Err(AppSW::BadState);
+        }
+        let (ty, name) = self.current_field()?;
+        match ty.levels.last() {
+            None => return Err(AppSW::BadState),
+            Some(Some(fixed)) if *fixed != size => return Err(AppSW::InvalidData),
+            Some(_) => (),
+        }
+        self.push_frame(Frame::Array {
+            ty: ty.element_type(),
+            name,
+            remaining: size,
+            hasher: Keccak256::new(),
+        })?;
+        self.settle()
+    }
+
+    /// Receives a chunk of the value of the atomic field expected next.
+    ///
+    /// The first chunk starts with the value length on 2 bytes (big endian).
+    pub fn add_value(&mut self, data: &[u8], more: bool) -> Result<(), AppSW> {
+        let data = match self.value_len {
+            Some(_) => data,
+            None => {
+                if data.len() < 2 {
+                    return Err(AppSW::WrongDataLength);
+                }
+                let len = u16::from_be_bytes([data[0], data[1]]) as usize;
+                if len > MAX_VALUE_LEN {
+                    return Err(AppSW::WrongDataLength);
+                }
+                self.value_len = Some(len);
+                self.value.clear();
+                &data[2..]
+            }
+        };
+        let len = self.value_len.unwrap_or_default();
+        if self.value.len() + data.len() > len {
+            return Err(AppSW::WrongDataLength);
+        }
+        self.value.extend_from_slice(data);
+
+        if more {
+            return Ok(());
+        }
+        if self.value.len() != len {
+            return Err(AppSW::WrongDataLength);
+        }
+        self.value_len = None;
+        let value = core::mem::take(&mut self.value);
+        self.process_value(&value)
+    }
+
+    fn process_value(&mut self, value: &[u8]) -> Result<(), AppSW> {
+        let (ty, name) = self.current_field()?;
+        if !ty.levels.is_empty() {
+            return Err(AppSW::BadState);
+        }
+
+        let mut word = [0u8; 32];
+        let display = match ty.base {
+            BaseType::Uint(size) | BaseType::Int(size) => {
+                if value.len() > size as usize {
+                    return Err(AppSW::InvalidData);
+                }
+                let negative = matches!(ty.base, BaseType::Int(_))
+                    && value.first().is_some_and(|b| b & 0x80 != 0);
+                if negative {
+                    word = [0xff; 32];
+                }
+                word[32 - value.len()..].copy_from_slice(value);
+                let n = U256::from_big_endian(&word);
+                if negative {
+                    DisplayValue::Text(format!("-{}", (!n).overflowing_add(U256::one()).0))
+                } else {
+                    DisplayValue::Text(n.to_string())
+                }
+            }
+            BaseType::Address => {
+                let addr: [u8; ADDRRESS_BYTES_LEN] =
+                    value.try_into().map_err(|_| AppSW::InvalidData)?;
+                word[32 - ADDRRESS_BYTES_LEN..].copy_from_slice(&addr);
+                DisplayValue::Address(addr)
+            }
+            BaseType::Bool => match value {
+                [0] => DisplayValue::Text("false".into()),
+                [1] => {
+                    word[31] = 1;
+                    DisplayValue::Text("true".into())
+                }
+                _ => return Err(AppSW::InvalidData),
+            },
+            BaseType::FixedBytes(size) => {
+                if value.len() != size as usize {
+                    return Err(AppSW::InvalidData);
+                }
+                word[..value.len()].copy_from_slice(value);
+                DisplayValue::Text(format!("0x{}", hex::encode(value).to_uppercase()))
+            }
+            BaseType::String => {
+                word = keccak(&[value])?;
+                // Only printable ASCII is displayed as is, as device fonts
+                // only render ASCII
+                let printable = value
+                    .iter()
+                    .all(|c| *c == b'\n' || *c == b' ' || c.is_ascii_graphic());
+                DisplayValue::Text(match printable {
+                    true => value.iter().map(|c| *c as char).collect(),
+                    false => format!("0x{}", hex::encode(value).to_uppercase()),
+                })
+            }
+            BaseType::Bytes => {
+                word = keccak(&[value])?;
+                DisplayValue::Text(format!("0x{}", hex::encode(value).to_uppercase()))
+            }
+            BaseType::Struct(_) => return Err(AppSW::BadState),
+        };
+
+        // Only known networks may be used in the domain
+        if self.root == Some(Root::Domain)
+            && self.frames.len() == 1
+            && name == "chainId"
+            && matches!(ty.base, BaseType::Uint(_))
+        {
+            let chain_id = U256::from_big_endian(&word);
+            if chain_id > U256::from(u64::MAX) {
+                return Err(AppSW::InvalidData);
+            }
+            let chain_id = chain_id.as_u64();
+            if !matches!(
+                Network::from_network_id(chain_id),
+                Network::Main | Network::Test
+            ) {
+                return Err(AppSW::InvalidData);
+            }
+            self.chain_id = Some(chain_id);
+        }
+
+        let frame = self.frames.last_mut().ok_or(AppSW::BadState)?;
+        frame.update(&word)?;
+        frame.advance();
+        self.fields.push(DisplayField {
+            name,
+            value: display,
+        });
+        self.settle()
+    }
+
+    // Type and path of the field expected next, e.g. `to.wallet`.
+    fn current_field(&self) -> Result<(FieldType, String), AppSW> {
+        match self.frames.last() {
+            Some(Frame::Struct {
+                index, field, path, ..
+            }) => {
+                let field = self.structs[*index]
+                    .fields
+                    .get(*field)
+                    .ok_or(AppSW::BadState)?;
+                let name = match path.is_empty() {
+                    true => field.name.clone(),
+                    false => format!("{}.{}", path, field.name),
+                };
+                Ok((field.ty.clone(), name))
+            }
+            Some(Frame::Array { ty, name, .. }) => Ok((ty.clone(), name.clone())),
+            None => Err(AppSW::BadState),
+        }
+    }
+
+    fn struct_index(&self, name: &str) -> Result<usize, AppSW> {
+        self.structs
+            .iter()
+            .position(|s| s.name == name)
+            .ok_or(AppSW::InvalidData)
+    }
+
+    fn push_frame(&mut self, frame: Frame) -> Result<(), AppSW> {
+        if self.frames.len() >= MAX_DEPTH {
+            return Err(AppSW::InvalidData);
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    fn push_struct(&mut self, index: usize, path: String) -> Result<(), AppSW> {
+        let type_hash = self.type_hash(index)?;
+        let mut hasher = Keccak256::new();
+        hasher.update(&type_hash).map_err(|_| AppSW::TxHashFail)?;
+        self.push_frame(Frame::Struct {
+            index,
+            field: 0,
+            path,
+            hasher,
+        })
+    }
+
+    // Closes completed frames and opens nested structs until an atomic
+    // value or an array size is expected.
+    fn settle(&mut self) -> Result<(), AppSW> {
+        loop {
+            let complete = match self.frames.last() {
+                None => return Ok(()),
+                Some(Frame::Struct { index, field, .. }) => {
+                    *field >= self.structs[*index].fields.len()
+                }
+                Some(Frame::Array { remaining, .. }) => *remaining == 0,
+            };
+
+            if complete {
+                let hash = self.frames.pop().ok_or(AppSW::BadState)?.finalize()?;
+                match self.frames.last_mut() {
+                    Some(parent) => {
+                        parent.update(&hash)?;
+                        parent.advance();
+                    }
+                    None => self.finish_root(hash)?,
+                }
+                continue;
+            }
+
+            let (ty, path) = self.current_field()?;
+            match ty.base {
+                BaseType::Struct(name) if ty.levels.is_empty() => {
+                    let index = self.struct_index(&name)?;
+                    self.push_struct(index, path)?;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn finish_root(&mut self, hash: [u8; HASH_BYTES_LEN]) -> Result<(), AppSW> {
+        match self.root.take() {
+            Some(Root::Domain) => {
+                if self.chain_id.is_none() {
+                    return Err(AppSW::InvalidData);
+                }
+                self.domain_hash = Some(hash);
+            }
+            Some(Root::Message) => self.message_hash = Some(hash),
+            None => return Err(AppSW::BadState),
+        }
+        Ok(())
+    }
+
+    /// `encodeType` of a struct: its own encoding followed by the encodings of
+    /// every referenced struct, sorted by name.
+    fn encode_type(&self, index: usize) -> Result<String, AppSW> {
+        let mut deps: Vec<usize> = Vec::new();
+        self.collect_deps(index, &mut deps)?;
+        deps.retain(|i| *i != index);
+        deps.sort_by(|a, b| self.structs[*a].name.cmp(&self.structs[*b].name));
+
+        let mut encoded = encode_struct(&self.structs[index]);
+        for i in deps {
+            encoded.push_str(&encode_struct(&self.structs[i]));
+        }
+        Ok(encoded)
+    }
+
+    fn collect_deps(&self, index: usize, deps: &mut Vec<usize>) -> Result<(), AppSW> {
+        if deps.contains(&index) {
+            return Ok(());
+        }
+        deps.push(index);
+        for field in self.structs[index].fields.iter() {
+            if let BaseType::Struct(name) = &field.ty.base {
+                self.collect_deps(self.struct_index(name)?, deps)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn type_hash(&self, index: usize) -> Result<[u8; HASH_BYTES_LEN], AppSW> {
+        keccak(&[self.encode_type(index)?.as_bytes()])
+    }
+}
+
+fn encode_struct(def: &StructDef) -> String {
+    let fields: Vec<String> = def
+        .fields
+        .iter()
+        .map(|f| format!("{} {}", f.ty.type_name(), f.name))
+        .collect();
+    format!("{}({})", def.name, fields.join(","))
+}
+
+fn keccak(parts: &[&[u8]]) -> Result<[u8; HASH_BYTES_LEN], AppSW> {
+    let mut keccak256 = Keccak256::new();
+    let mut hash = [0u8; HASH_BYTES_LEN];
+    for part in parts {
+        keccak256.update(part).map_err(|_| AppSW::TxHashFail)?;
+    }
+    keccak256
+        .finalize(&mut hash)
+        .map_err(|_| AppSW::TxHashFail)?;
+    Ok(hash)
+}
+
+// Struct and field names end up in `encodeType`, so only identifiers are accepted.
+fn parse_name(name: &[u8]) -> Result<String, AppSW> {
+    if name.is_empty()
+        || !name
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || *c == b'_' || *c == b'$')
+    {
+        return Err(AppSW::InvalidData);
+    }
+    // Identifiers are ASCII, this cannot fail
+    core::str::from_utf8(name)
+        .map(|s| s.into())
+        .map_err(|_| AppSW::InvalidData)
+}
+
+/// Parses a field definition:
+///
+/// | Description                                   | Length |
+/// | --------------------------------------------- | ------ |
+/// | Type descriptor (array flag, size flag, id)   | 1      |
+/// | Struct name length and name (custom types)    | 1 + var |
+/// | Type size in bytes (when size flag is set)    | 1      |
+/// | Array level count and levels (arrays)         | 1 + var |
+/// | Field name length and name                    | 1 + var |
+///
+/// Each array level is `0x00` for a dynamic level or `0x01` followed by the
+/// level size for a fixed one.
+fn parse_field(data: &[u8]) -> Result<FieldDef, AppSW> {
+    let mut reader = Reader(data);
+    let descriptor = reader.byte()?;
+    let type_id = descriptor & TYPE_ID_MASK;
+    let is_array = descriptor & TYPE_ARRAY_FLAG != 0;
+    let has_size = descriptor & TYPE_SIZE_FLAG != 0;
+
+    let base = match (type_id, has_size) {
+        (TYPE_ID_CUSTOM, false) => BaseType::Struct(parse_name(reader.sized()?)?),
+        (TYPE_ID_INT, true) => BaseType::Int(reader.type_size()?),
+        (TYPE_ID_UINT, true) => BaseType::Uint(reader.type_size()?),
+        (TYPE_ID_ADDRESS, false) => BaseType::Address,
+        (TYPE_ID_BOOL, false) => BaseType::Bool,
+        (TYPE_ID_STRING, false) => BaseType::String,
+        (TYPE_ID_FIXED_BYTES, true) => BaseType::FixedBytes(reader.type_size()?),
+        (TYPE_ID_DYNAMIC_BYTES, false) => BaseType::Bytes,
+        (_, _) => return Err(AppSW::InvalidData),
+    };
+
+    let mut levels = Vec::new();
+    if is_array {
+        let count = reader.byte()?;
+        if count == 0 || count as usize > MAX_DEPTH {
+            return Err(AppSW::InvalidData);
+        }
+        for _ in 0..count {
+            match reader.byte()? {
+                ARRAY_LEVEL_DYNAMIC => levels.push(None),
+                ARRAY_LEVEL_FIXED => levels.push(Some(reader.byte()?)),
+                _ => return Err(AppSW::InvalidData),
+            }
+        }
+    }
+
+    let name = parse_name(reader.sized()?)?;
+    if !reader.0.is_empty() {
+        return Err(AppSW::WrongDataLength);
+    }
+
+    Ok(FieldDef {
+        ty: FieldType { base, levels },
+        name,
+    })
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, AppSW> {
+        let (first, rest) = self.0.split_first().ok_or(AppSW::WrongDataLength)?;
+        self.0 = rest;
+        Ok(*first)
+    }
+
+    // Length-prefixed bytes
+    fn sized(&mut self) -> Result<&'a [u8], AppSW> {
+        let len = self.byte()? as usize;
+        if self.0.len() < len {
+            return Err(AppSW::WrongDataLength);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    // Integer or fixed bytes size, between 1 and 32 bytes
+    fn type_size(&mut self) -> Result<u8, AppSW> {
+        match self.byte()? {
+            size @ 1..=32 => Ok(size),
+            _ => Err(AppSW::InvalidData),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use super::record::Reader;
+use crate::consts::{ADDRRESS_BYTES_LEN, MAX_CALL_DESCRIPTORS};
+use crate::AppSW;
+use alloc::{string::String, vec::Vec};
+
+/// First byte of a call descriptor record, which no token record starts with.
+pub const DESCRIPTOR_RECORD_TYPE: u8 = 0x00;
+/// Maximum length of the operation of a call descriptor.
+pub const MAX_OPERATION_LEN: usize = 32;
+/// Maximum length of a parameter name.
+pub const MAX_PARAM_NAME_LEN: usize = 20;
+/// Maximum number of parameters of a call descriptor.
+pub const MAX_PARAMS: usize = 8;
+
+// Token of a token amount held by the called contract itself.
+const TOKEN_CONTRACT: u8 = 0xff;
+
+/// ABI type of a parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamType {
+    Uint,
+    Address,
+    Bool,
+    Bytes32,
+    Bytes,
+    String,
+}
+
+impl ParamType {
+    /// Whether the value of the parameter follows the arguments.
+    pub fn is_dynamic(&self) -> bool {
+        matches!(self, ParamType::Bytes | ParamType::String)
+    }
+}
+
+impl TryFrom<u8> for ParamType {
+    type Error = AppSW;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ParamType::Uint),
+            1 => Ok(ParamType::Address),
+            2 => Ok(ParamType::Bool),
+            3 => Ok(ParamType::Bytes32),
+            4 => Ok(ParamType::Bytes),
+            5 => Ok(ParamType::String),
+            _ => Err(AppSW::InvalidData),
+        }
+    }
+}
+
+/// How a parameter is displayed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamFormat {
+    /// As its ABI type.
+    Raw,
+    /// As an amount of Drip, in CFX.
+    Amount,
+    /// As a Unix timestamp, in UTC.
+    Date,
+    /// As an amount of a token.
+    TokenAmount(TokenRef),
+}
+
+/// Token of a token amount.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenRef {
+    /// The called contract.
+    Contract,
+    /// The address parameter at this index.
+    Param(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamDescriptor {
+    pub name: String,
+    pub kind: ParamType,
+    pub format: ParamFormat,
+}
+
+/// Description of a contract function, used to review its calls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallDescriptor {
+    pub address: [u8; ADDRRESS_BYTES_LEN],
+    pub chain_id: u64,
+    pub selector: [u8; 4],
+    /// What the call does, completing "Review transaction to ...".
+    pub operation: String,
+    pub params: Vec<ParamDescriptor>,
+}
+
+/// Call descriptor record received from the host, along with its signature.
+///
+/// The record is encoded as `record type (1) || chain id (4, big endian) ||
+/// address (20) || selector (4) || operation length (1) || operation ||
+/// parameter count (1) || parameters`, each parameter being encoded as
+/// `type (1) || format (1) || token (1, token amounts only) || name length (1)
+/// || name`. It is followed by the DER signature of its SHA-256 hash.
+pub struct DescriptorRecord<'a> {
+    pub descriptor: CallDescriptor,
+    /// Signed part of the record.
+    pub signed: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for DescriptorRecord<'a> {
+    type Error = AppSW;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = Reader::new(data);
+        if reader.u8()? != DESCRIPTOR_RECORD_TYPE {
+            return Err(AppSW::InvalidData);
+        }
+        let chain_id = u32::from_be_bytes(reader.array()?) as u64;
+        let address = reader.array()?;
+        let selector = reader.array()?;
+        let operation = reader.text(MAX_OPERATION_LEN)?;
+
+        let count = reader.u8()? as usize;
+        if count > MAX_PARAMS {
+            return Err(AppSW::InvalidData);
+        }
+        let mut params = Vec::with_capacity(count);
+        for _ in 0..count {
+            let kind = ParamType::try_from(reader.u8()?)?;
+            let format = match reader.u8()? {
+                0 => ParamFormat::Raw,
+                1 => ParamFormat::Amount,
+                2 => ParamFormat::Date,
+                3 => ParamFormat::TokenAmount(match reader.u8()? {
+                    TOKEN_CONTRACT => TokenRef::Contract,
+                    index => TokenRef::Param(index as usize),
+                }),
+                _ => return Err(AppSW::InvalidData),
+            };
+            // Only integers have a display format
+            if format != ParamFormat::Raw && kind != ParamType::Uint {
+                return Err(AppSW::InvalidData);
+            }
+            let name = reader.text(MAX_PARAM_NAME_LEN)?;
+            params.push(ParamDescriptor { name, kind, format });
+        }
+        // Tokens are given by address parameters
+        for param in params.iter() {
+            if let ParamFormat::TokenAmount(TokenRef::Param(index)) = param.format {
+                if params.get(index).map(|p| p.kind) != Some(ParamType::Address) {
+                    return Err(AppSW::InvalidData);
+                }
+            }
+        }
+
+        let (signed, signature) = reader.signed()?;
+        Ok(DescriptorRecord {
+            descriptor: CallDescriptor {
+                address,
+                chain_id,
+                selector,
+                operation,
+                params,
+            },
+            signed,
+            signature,
+        })
+    }
+}
+
+/// Verified call descriptors, the oldest entries are dropped first when full.
+#[derive(Default)]
+pub struct DescriptorCache {
+    descriptors: Vec<CallDescriptor>,
+}
+
+impl DescriptorCache {
+    pub fn add(&mut self, descriptor: CallDescriptor) {
+        self.descriptors.retain(|d| {
+            d.address != descriptor.address
+                || d.chain_id != descriptor.chain_id
+                || d.selector != descriptor.selector
+        });
+        if self.descriptors.len() == MAX_CALL_DESCRIPTORS {
+            self.descriptors.remove(0);
+        }
+        self.descriptors.push(descriptor);
+    }
+
+    pub fn get(
+        &self,
+        address: &[u8; ADDRRESS_BYTES_LEN],
+        chain_id: u64,
+        selector: [u8; 4],
+    ) -> Option<&CallDescriptor> {
+        self.descriptors
+            .iter()
+            .find(|d| &d.address == address && d.chain_id == chain_id && d.selector == selector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // deposit(uint256 amount, address token, uint256 deadline)
+    fn record(params: &[&[u8]]) -> Vec<u8> {
+        let mut data = alloc::vec![DESCRIPTOR_RECORD_TYPE];
+        data.extend_from_slice(&1029u32.to_be_bytes());
+        data.extend_from_slice(&[0x8c; 20]);
+        data.extend_from_slice(&[0xb6, 0xb5, 0x5f, 0x25]);
+        data.push(7);
+        data.extend_from_slice(b"deposit");
+        data.push(params.len() as u8);
+        for param in params {
+            data.extend_from_slice(param);
+        }
+        data
+    }
+
+    const AMOUNT: &[u8] = b"\x00\x03\x01\x06Amount";
+    const TOKEN: &[u8] = b"\x01\x00\x05Token";
+    const DEADLINE: &[u8] = b"\x00\x02\x08Deadline";
+
+    #[test]
+    fn parse_record() {
+        let mut data = record(&[AMOUNT, TOKEN, DEADLINE]);
+        let signed_len = data.len();
+        data.extend_from_slice(&[0x30, 0x44]);
+        let record = DescriptorRecord::try_from(data.as_slice()).unwrap();
+        let descriptor = record.descriptor;
+        assert_eq!(descriptor.chain_id, 1029);
+        assert_eq!(descriptor.operation, "deposit");
+        assert_eq!(descriptor.params.len(), 3);
+        assert_eq!(
+            descriptor.params[0].format,
+            ParamFormat::TokenAmount(TokenRef::Param(1))
+        );
+        assert_eq!(descriptor.params[1].kind, ParamType::Address);
+        assert_eq!(descriptor.params[2].format, ParamFormat::Date);
+        assert_eq!(record.signed, &data[..signed_len]);
+        assert_eq!(record.signature, &[0x30, 0x44]);
+    }
+
+    #[test]
+    fn reject_malformed_record() {
+        // No signature
+        let data = record(&[AMOUNT, TOKEN, DEADLINE]);
+        assert!(DescriptorRecord::try_from(data.as_slice()).is_err());
+        // Token amount of a parameter that is not an address
+        let mut data = record(&[AMOUNT, DEADLINE]);
+        data.push(0x30);
+        assert!(DescriptorRecord::try_from(data.as_slice()).is_err());
+        // Date format for an address
+        let mut data = record(&[b"\x01\x02\x04Date"]);
+        data.push(0x30);
+        assert!(DescriptorRecord::try_from(data.as_slice()).is_err());
+        // Token record
+        let mut data = record(&[TOKEN]);
+        data[0] = 4;
+        data.push(0x30);
+        assert!(DescriptorRecord::try_from(data.as_slice()).is_err());
+    }
+}
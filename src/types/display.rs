@@ -0,0 +1,65 @@
+use super::NameCache;
+use crate::cfx_addr::{cfx_addr_encode, Network};
+use crate::consts::ADDRRESS_BYTES_LEN;
+use crate::utils::eip55_encode;
+use crate::AppSW;
+use alloc::{format, string::String};
+
+/// Value of a reviewed field. Addresses are kept raw so they can be encoded
+/// for the network of the request once displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisplayValue {
+    Text(String),
+    Address([u8; ADDRRESS_BYTES_LEN]),
+    /// eSpace address, displayed in hex whatever the space of the request.
+    EspaceAddress([u8; ADDRRESS_BYTES_LEN]),
+}
+
+impl DisplayValue {
+    /// Formats the value, addresses in base32 for `network`, or in hex for eSpace.
+    pub fn format(&self, network: Network, espace: bool) -> Result<String, AppSW> {
+        match self {
+            DisplayValue::Text(text) => Ok(text.clone()),
+            DisplayValue::Address(addr) if espace => eip55_encode(addr),
+            DisplayValue::EspaceAddress(addr) => eip55_encode(addr),
+            DisplayValue::Address(addr) => {
+                cfx_addr_encode(addr, network).map_err(|_e| AppSW::AddrDisplayFail)
+            }
+        }
+    }
+
+    /// Returns the address of the value if it is on the chain of the request.
+    pub fn chain_address(&self, espace: bool) -> Option<&[u8; ADDRRESS_BYTES_LEN]> {
+        match self {
+            DisplayValue::Address(addr) => Some(addr),
+            // eSpace addresses of Core requests are on another chain
+            DisplayValue::EspaceAddress(addr) if espace => Some(addr),
+            _ => None,
+        }
+    }
+
+    /// Formats the value as [`DisplayValue::format`], addresses having a
+    /// trusted name on `chain_id` being preceded by this name.
+    pub fn format_named(
+        &self,
+        network: Network,
+        espace: bool,
+        chain_id: u64,
+        names: &NameCache,
+    ) -> Result<String, AppSW> {
+        let value = self.format(network, espace)?;
+        let name = self
+            .chain_address(espace)
+            .and_then(|addr| names.get(addr, chain_id));
+        Ok(match name {
+            Some(name) => format!("{} ({}): {}", name.name, name.source.label(), value),
+            None => value,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayField {
+    pub name: String,
+    pub value: DisplayValue,
+}
@@ -1,5 +1,20 @@
+pub mod cip23;
+mod descriptor;
+mod display;
 mod primitives;
+mod record;
+mod token;
 mod transaction;
+mod trusted_name;
+mod tx_parser;
 
+pub use cip23::Cip23Context;
+pub use descriptor::{
+    CallDescriptor, DescriptorCache, DescriptorRecord, ParamFormat, ParamType, TokenRef,
+};
+pub use display::{DisplayField, DisplayValue};
 pub use primitives::{Address, H256, U256};
+pub use token::{TokenCache, TokenInfo, TokenRecord};
 pub use transaction::Transaction;
+pub use trusted_name::{NameCache, NameRecord};
+pub use tx_parser::TxParser;
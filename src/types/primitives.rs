@@ -1,7 +1,10 @@
 #![allow(clippy::manual_div_ceil)]
 
 use crate::consts::{ADDRRESS_BYTES_LEN, EXPONENT_SMALLEST_UNIT, HASH_BYTES_LEN};
-use alloc::string::{String, ToString};
+use alloc::{
+    format,
+    string::{String, ToString},
+};
 use bigdecimal::{BigDecimal, FromPrimitive};
 use core::cmp::Ordering;
 use core::ops::Deref;
@@ -21,6 +24,13 @@ impl U256 {
         let eth_conversion = BigDecimal::from_i64(10_i64.pow(EXPONENT_SMALLEST_UNIT as u32))?;
         Some((wei / eth_conversion).to_string())
     }
+
+    /// Formats an amount of a token with `decimals` decimals, without
+    /// exponent or trailing zeros.
+    pub fn decimal_str(&self, decimals: u8) -> Option<String> {
+        let amount = BigDecimal::from_str(&format!("{}e-{}", self, decimals)).ok()?;
+        Some(amount.normalized().to_plain_string())
+    }
 }
 
 impl Decodable for U256 {
@@ -67,12 +77,10 @@ impl Address {
         self.address_type() == 0x10
     }
 
-    #[allow(unused)]
     pub fn is_contract_address(&self) -> bool {
         self.address_type() == 0x80
     }
 
-    #[allow(unused)]
     pub fn is_builtin_address(&self) -> bool {
         self.address_type() == 0x00
     }
@@ -0,0 +1,55 @@
+use crate::AppSW;
+use alloc::string::String;
+
+/// Reader of the fields of a signed record provided by the host.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    pub fn bytes(&mut self, len: usize) -> Result<&'a [u8], AppSW> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(AppSW::InvalidData)?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, AppSW> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N], AppSW> {
+        Ok(self.bytes(N)?.try_into().unwrap())
+    }
+
+    /// Reads a length-prefixed text, made of printable ASCII and spaces as it
+    /// is displayed as is.
+    pub fn text(&mut self, max_len: usize) -> Result<String, AppSW> {
+        let len = self.u8()? as usize;
+        if len == 0 || len > max_len {
+            return Err(AppSW::InvalidData);
+        }
+        let text = self.bytes(len)?;
+        if !text.iter().all(|c| *c == b' ' || c.is_ascii_graphic()) {
+            return Err(AppSW::InvalidData);
+        }
+        String::from_utf8(text.into()).map_err(|_| AppSW::InvalidData)
+    }
+
+    /// Splits the record into the fields read, which are signed, and the
+    /// signature that follows them.
+    pub fn signed(&self) -> Result<(&'a [u8], &'a [u8]), AppSW> {
+        let (signed, signature) = self.data.split_at(self.pos);
+        if signature.is_empty() {
+            return Err(AppSW::InvalidData);
+        }
+        Ok((signed, signature))
+    }
+}
@@ -0,0 +1,193 @@
+use crate::consts::{ADDRRESS_BYTES_LEN, MAX_TOKEN_INFOS};
+use crate::AppSW;
+use alloc::{string::String, vec::Vec};
+
+/// Maximum length of a token ticker.
+pub const MAX_TICKER_LEN: usize = 12;
+
+/// Metadata of a CRC-20 / ERC-20 token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenInfo {
+    pub address: [u8; ADDRRESS_BYTES_LEN],
+    pub chain_id: u64,
+    pub ticker: String,
+    pub decimals: u8,
+}
+
+/// Token metadata record received from the host, along with its signature.
+///
+/// The record is encoded as `ticker length (1) || ticker || address (20) ||
+/// decimals (1) || chain id (4, big endian)`, followed by the DER signature of
+/// its SHA-256 hash.
+pub struct TokenRecord<'a> {
+    pub info: TokenInfo,
+    /// Signed part of the record.
+    pub signed: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for TokenRecord<'a> {
+    type Error = AppSW;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let ticker_len = *data.first().ok_or(AppSW::InvalidData)? as usize;
+        if ticker_len == 0 || ticker_len > MAX_TICKER_LEN {
+            return Err(AppSW::InvalidData);
+        }
+        let signed_len = 1 + ticker_len + ADDRRESS_BYTES_LEN + 1 + 4;
+        if data.len() <= signed_len {
+            return Err(AppSW::InvalidData);
+        }
+        let (signed, signature) = data.split_at(signed_len);
+
+        // Only printable ASCII tickers, they are displayed as is
+        let ticker = &signed[1..1 + ticker_len];
+        if !ticker.iter().all(|c| c.is_ascii_graphic()) {
+            return Err(AppSW::InvalidData);
+        }
+        let rest = &signed[1 + ticker_len..];
+        let info = TokenInfo {
+            address: rest[..ADDRRESS_BYTES_LEN].try_into().unwrap(),
+            decimals: rest[ADDRRESS_BYTES_LEN],
+            chain_id: u32::from_be_bytes(rest[ADDRRESS_BYTES_LEN + 1..].try_into().unwrap()) as u64,
+            ticker: String::from_utf8(ticker.into()).map_err(|_| AppSW::InvalidData)?,
+        };
+
+        Ok(TokenRecord {
+            info,
+            signed,
+            signature,
+        })
+    }
+}
+
+/// Token compiled into the app from the token list `tokens.csv`.
+struct KnownToken {
+    chain_id: u64,
+    address: [u8; ADDRRESS_BYTES_LEN],
+    ticker: &'static str,
+    decimals: u8,
+}
+
+// Sorted by chain id and address
+include!(concat!(env!("OUT_DIR"), "/tokens.rs"));
+
+/// Verified token metadata, the oldest entries are dropped first when full.
+///
+/// Provided tokens take precedence over the tokens compiled into the app.
+#[derive(Default)]
+pub struct TokenCache {
+    tokens: Vec<TokenInfo>,
+}
+
+impl TokenCache {
+    pub fn add(&mut self, info: TokenInfo) {
+        self.tokens
+            .retain(|t| t.address != info.address || t.chain_id != info.chain_id);
+        if self.tokens.len() == MAX_TOKEN_INFOS {
+            self.tokens.remove(0);
+        }
+        self.tokens.push(info);
+    }
+
+    pub fn get(&self, address: &[u8; ADDRRESS_BYTES_LEN], chain_id: u64) -> Option<TokenInfo> {
+        if let Some(info) = self
+            .tokens
+            .iter()
+            .find(|t| &t.address == address && t.chain_id == chain_id)
+        {
+            return Some(info.clone());
+        }
+        let i = KNOWN_TOKENS
+            .binary_search_by(|t| (t.chain_id, &t.address).cmp(&(chain_id, address)))
+            .ok()?;
+        let known = &KNOWN_TOKENS[i];
+        Some(TokenInfo {
+            address: known.address,
+            chain_id: known.chain_id,
+            ticker: known.ticker.into(),
+            decimals: known.decimals,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(ticker: &[u8], chain_id: u32) -> Vec<u8> {
+        let mut data = alloc::vec![ticker.len() as u8];
+        data.extend_from_slice(ticker);
+        data.extend_from_slice(&[0x8b; 20]);
+        data.push(6);
+        data.extend_from_slice(&chain_id.to_be_bytes());
+        data
+    }
+
+    fn token(address: u8, chain_id: u64) -> TokenInfo {
+        TokenInfo {
+            address: [address; 20],
+            chain_id,
+            ticker: "USDT".into(),
+            decimals: 6,
+        }
+    }
+
+    #[test]
+    fn parse_record() {
+        let mut data = record(b"USDT", 1029);
+        data.extend_from_slice(&[0x30, 0x44]);
+        let record = TokenRecord::try_from(data.as_slice()).unwrap();
+        assert_eq!(record.info, token(0x8b, 1029));
+        assert_eq!(record.signed, &data[..30]);
+        assert_eq!(record.signature, &[0x30, 0x44]);
+    }
+
+    #[test]
+    fn reject_malformed_record() {
+        // No signature
+        let data = record(b"USDT", 1029);
+        assert!(TokenRecord::try_from(data.as_slice()).is_err());
+        // Ticker not printable
+        let mut data = record(b"US\nT", 1029);
+        data.push(0x30);
+        assert!(TokenRecord::try_from(data.as_slice()).is_err());
+        // Ticker too long
+        let mut data = record(&[b'A'; MAX_TICKER_LEN + 1], 1029);
+        data.push(0x30);
+        assert!(TokenRecord::try_from(data.as_slice()).is_err());
+    }
+
+    #[test]
+    fn cache_drops_oldest() {
+        let mut cache = TokenCache::default();
+        for i in 0..=MAX_TOKEN_INFOS as u8 {
+            cache.add(token(i, 1029));
+        }
+        assert!(cache.get(&[0; 20], 1029).is_none());
+        assert!(cache.get(&[1; 20], 1029).is_some());
+        assert!(cache.get(&[1; 20], 1030).is_none());
+    }
+
+    #[test]
+    fn known_tokens() {
+        const CUSDT: [u8; 20] = [
+            0x8b, 0x86, 0x89, 0xc7, 0xf3, 0x01, 0x4a, 0x4d, 0x86, 0xe4, 0xd1, 0xd0, 0xda, 0xaf,
+            0x74, 0xa4, 0x7f, 0x5e, 0x0f, 0x27,
+        ];
+        let mut cache = TokenCache::default();
+        assert_eq!(cache.get(&CUSDT, 1029).unwrap().ticker, "cUSDT");
+        assert!(cache.get(&CUSDT, 1).is_none());
+
+        // Provided tokens override the built-in ones
+        cache.add(TokenInfo {
+            ticker: "USDT".into(),
+            ..token(0x8b, 1029)
+        });
+        cache.add(TokenInfo {
+            address: CUSDT,
+            ..token(0, 1029)
+        });
+        assert_eq!(cache.get(&CUSDT, 1029).unwrap().decimals, 6);
+    }
+}
@@ -1,19 +1,39 @@
 use super::{Address, H256, U256};
 use crate::consts::STORAGE_OF_ONE_CFX;
 use alloc::vec::Vec;
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
 use rlp_decoder::{Decodable, DecoderError, Rlp};
 
 pub const TX_RLP_PREFIX_2930: [u8; 4] = [0x63, 0x66, 0x78, 0x01]; // "cfx" + 1
 pub const TX_RLP_PREFIX_1559: [u8; 4] = [0x63, 0x66, 0x78, 0x02]; // "cfx" + 2
+pub const ESPACE_TX_TYPE_2930: u8 = 0x01;
+pub const ESPACE_TX_TYPE_1559: u8 = 0x02;
 pub const ONE_CFX_IN_DRIP: u64 = 1_000_000_000_000_000_000;
 
+/// Transaction encodings, the Core ones and their eSpace (EVM) counterparts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TxType {
+    #[default]
+    Legacy,
+    Eip2930,
+    Eip1559,
+    EspaceLegacy,
+    EspaceEip2930,
+    EspaceEip1559,
+}
+
 #[allow(dead_code)]
 #[derive(Debug, Default, Clone)]
 pub struct Transaction {
-    pub to: Address,
+    /// Recipient, `None` for contract creation.
+    pub to: Option<Address>,
     pub value: U256,
     pub nonce: u64,
     pub data: Vec<u8>,
+    /// Length of the transaction data, `data` may only hold its first bytes.
+    pub data_len: usize,
+    /// Keccak256 hash of the whole transaction data.
+    pub data_hash: H256,
     pub gas: u64,
     pub gas_price: Option<U256>,
     pub storage_limit: u64,
@@ -22,6 +42,7 @@ pub struct Transaction {
     pub access_list: Option<AccessList>,
     pub max_priority_fee_per_gas: Option<U256>,
     pub max_fee_per_gas: Option<U256>,
+    pub tx_type: TxType,
 }
 
 impl Transaction {
@@ -38,11 +59,43 @@ impl Transaction {
             / U256::from(STORAGE_OF_ONE_CFX)
     }
 
+    // whether only the first bytes of the data were kept
+    pub fn data_truncated(&self) -> bool {
+        self.data.len() < self.data_len
+    }
+
+    // whether the tx deploys a contract
+    pub fn is_contract_creation(&self) -> bool {
+        self.to.is_none()
+    }
+
+    // whether the tx is an eSpace (EVM) transaction
+    pub fn is_espace(&self) -> bool {
+        matches!(
+            self.tx_type,
+            TxType::EspaceLegacy | TxType::EspaceEip2930 | TxType::EspaceEip1559
+        )
+    }
+
     // whether the tx is fully decoded
-    // when the tx is to a contract address, the data field is not empty
-    // we call it not fully decoded
+    // when the tx is to a contract address or deploys a contract,
+    // the data field is not empty, we call it not fully decoded
+    // eSpace addresses carry no type, so any data makes it not fully decoded
     pub fn fully_decoded(&self) -> bool {
-        self.data.is_empty() || self.to.is_user_address()
+        self.data.is_empty()
+            || (!self.is_espace() && self.to.as_ref().is_some_and(|to| to.is_user_address()))
+    }
+
+    // v returned with the signature: EIP-155 `chain_id * 2 + 35 + parity` for
+    // eSpace legacy transactions, truncated to one byte as in the Ethereum app,
+    // and the y parity for every other transaction type
+    pub fn signature_v(&self, parity: u8) -> u8 {
+        match self.tx_type {
+            TxType::EspaceLegacy => {
+                (self.chain_id.wrapping_mul(2).wrapping_add(35) as u8).wrapping_add(parity)
+            }
+            _ => parity,
+        }
     }
 }
 
@@ -63,12 +116,33 @@ impl Decodable for AccessListItem {
 
 pub type AccessList = Vec<AccessListItem>;
 
+/// Decodes the `to` field, an empty value means contract creation.
+pub fn decode_to(rlp: &Rlp) -> Result<Option<Address>, DecoderError> {
+    if rlp.is_data() && rlp.is_empty() {
+        Ok(None)
+    } else {
+        rlp.as_val().map(Some)
+    }
+}
+
 pub const TX_LEGACY_RLP_LEN: usize = 9;
 pub const TX_EIP2930_RLP_LEN: usize = 10;
 pub const TX_EIP1559_RLP_LEN: usize = 11;
+pub const ESPACE_TX_LEGACY_RLP_LEN: usize = 9;
+pub const ESPACE_TX_EIP2930_RLP_LEN: usize = 8;
+pub const ESPACE_TX_EIP1559_RLP_LEN: usize = 9;
 
 impl Decodable for Transaction {
     fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let mut tx = Self::decode_fields(rlp)?;
+        tx.data_len = tx.data.len();
+        let _ = Keccak256::new().hash(&tx.data, &mut tx.data_hash.0);
+        Ok(tx)
+    }
+}
+
+impl Transaction {
+    fn decode_fields(rlp: &Rlp) -> Result<Self, DecoderError> {
         if rlp.as_raw().is_empty() {
             return Err(DecoderError::RlpInvalidLength);
         };
@@ -80,15 +154,18 @@ impl Decodable for Transaction {
                 nonce: rlp.val_at(0)?,
                 gas_price: Some(rlp.val_at(1)?),
                 gas: rlp.val_at(2)?,
-                to: rlp.val_at(3)?,
+                to: decode_to(&rlp.at(3)?)?,
                 value: rlp.val_at(4)?,
                 storage_limit: rlp.val_at(5)?,
                 epoch_height: rlp.val_at(6)?,
                 chain_id: rlp.val_at(7)?,
                 data: rlp.val_at(8)?,
+                data_len: 0,
+                data_hash: Default::default(),
                 access_list: None,
                 max_priority_fee_per_gas: None,
                 max_fee_per_gas: None,
+                tx_type: TxType::Legacy,
             })
         } else {
             let data = rlp.as_raw();
@@ -106,15 +183,18 @@ impl Decodable for Transaction {
                         nonce: rlp.val_at(0)?,
                         gas_price: Some(rlp.val_at(1)?),
                         gas: rlp.val_at(2)?,
-                        to: rlp.val_at(3)?,
+                        to: decode_to(&rlp.at(3)?)?,
                         value: rlp.val_at(4)?,
                         storage_limit: rlp.val_at(5)?,
                         epoch_height: rlp.val_at(6)?,
                         chain_id: rlp.val_at(7)?,
                         data: rlp.val_at(8)?,
+                        data_len: 0,
+                        data_hash: Default::default(),
                         access_list: Some(rlp.list_at(9)?),
                         max_priority_fee_per_gas: None,
                         max_fee_per_gas: None,
+                        tx_type: TxType::Eip2930,
                     })
                 }
                 TX_RLP_PREFIX_1559 => {
@@ -127,13 +207,16 @@ impl Decodable for Transaction {
                         max_priority_fee_per_gas: Some(rlp.val_at(1)?),
                         max_fee_per_gas: Some(rlp.val_at(2)?),
                         gas: rlp.val_at(3)?,
-                        to: rlp.val_at(4)?,
+                        to: decode_to(&rlp.at(4)?)?,
                         value: rlp.val_at(5)?,
                         storage_limit: rlp.val_at(6)?,
                         epoch_height: rlp.val_at(7)?,
                         chain_id: rlp.val_at(8)?,
                         data: rlp.val_at(9)?,
+                        data_len: 0,
+                        data_hash: Default::default(),
                         access_list: Some(rlp.list_at(10)?),
+                        tx_type: TxType::Eip1559,
                     })
                 }
                 _ => Err(DecoderError::Custom("invalid raw transaction")),
@@ -156,10 +239,12 @@ mod tests {
                 .as_slice(),
         );
         let mut tx = Transaction {
-            to,
+            to: Some(to),
             value: U256::from(1),
             nonce: 1,
             data: Bytes::from(""),
+            data_len: 0,
+            data_hash: Default::default(),
             gas: 1,
             gas_price: Some(U256::from(1)),
             storage_limit: 1,
@@ -168,6 +253,7 @@ mod tests {
             access_list: None,
             max_priority_fee_per_gas: None,
             max_fee_per_gas: None,
+            tx_type: TxType::Legacy,
         };
         // let out = encode(&tx);
         let out = "dd0101019401234567890123456789012345678901234567890101010180"
@@ -206,10 +292,12 @@ mod tests {
                 .as_slice(),
         );
         let mut tx = Transaction {
-            to,
+            to: Some(to),
             value: U256::from(1),
             nonce: 1,
             data: Bytes::from("hello"),
+            data_len: 5,
+            data_hash: Default::default(),
             gas: 1,
             gas_price: Some(U256::from(1)),
             storage_limit: 1,
@@ -218,6 +306,7 @@ mod tests {
             access_list: Some(vec![]),
             max_priority_fee_per_gas: None,
             max_fee_per_gas: None,
+            tx_type: TxType::Eip2930,
         };
         // let out = encode(&tx);
         let out =
@@ -269,10 +358,12 @@ mod tests {
                 .as_slice(),
         );
         let mut tx = Transaction {
-            to,
+            to: Some(to),
             value: U256::from(1),
             nonce: 1,
             data: Bytes::from("hello"),
+            data_len: 5,
+            data_hash: Default::default(),
             gas: 1,
             gas_price: None,
             storage_limit: 1,
@@ -281,6 +372,7 @@ mod tests {
             access_list: None,
             max_priority_fee_per_gas: Some(U256::from(1)),
             max_fee_per_gas: Some(U256::from(1)),
+            tx_type: TxType::Eip1559,
         };
         // let out = encode(&tx);
         let out =
@@ -0,0 +1,150 @@
+use super::record::Reader;
+use crate::consts::{ADDRRESS_BYTES_LEN, MAX_TRUSTED_NAMES};
+use crate::AppSW;
+use alloc::{string::String, vec::Vec};
+
+/// First byte of a trusted name record, which no token record or call
+/// descriptor starts with.
+pub const NAME_RECORD_TYPE: u8 = 0x80;
+/// Maximum length of a trusted name.
+pub const MAX_NAME_LEN: usize = 30;
+
+/// Where a trusted name comes from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NameSource {
+    /// Conflux Name Service, names registered by the owners of the addresses.
+    Cns,
+    /// List of names curated by the signer.
+    Curated,
+}
+
+impl NameSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NameSource::Cns => "CNS",
+            NameSource::Curated => "curated",
+        }
+    }
+}
+
+/// Name of an address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustedName {
+    pub address: [u8; ADDRRESS_BYTES_LEN],
+    pub chain_id: u64,
+    pub name: String,
+    pub source: NameSource,
+}
+
+/// Trusted name record received from the host, along with its signature.
+///
+/// The record is encoded as `record type (1) || chain id (4, big endian) ||
+/// address (20) || source (1) || name length (1) || name`, followed by the DER
+/// signature of its SHA-256 hash.
+pub struct NameRecord<'a> {
+    pub name: TrustedName,
+    /// Signed part of the record.
+    pub signed: &'a [u8],
+    pub signature: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for NameRecord<'a> {
+    type Error = AppSW;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let mut reader = Reader::new(data);
+        if reader.u8()? != NAME_RECORD_TYPE {
+            return Err(AppSW::InvalidData);
+        }
+        let chain_id = u32::from_be_bytes(reader.array()?) as u64;
+        let address = reader.array()?;
+        let source = match reader.u8()? {
+            0 => NameSource::Cns,
+            1 => NameSource::Curated,
+            _ => return Err(AppSW::InvalidData),
+        };
+        let name = reader.text(MAX_NAME_LEN)?;
+
+        let (signed, signature) = reader.signed()?;
+        Ok(NameRecord {
+            name: TrustedName {
+                address,
+                chain_id,
+                name,
+                source,
+            },
+            signed,
+            signature,
+        })
+    }
+}
+
+/// Verified trusted names, the oldest entries are dropped first when full.
+#[derive(Default)]
+pub struct NameCache {
+    names: Vec<TrustedName>,
+}
+
+impl NameCache {
+    pub fn add(&mut self, name: TrustedName) {
+        self.names
+            .retain(|n| n.address != name.address || n.chain_id != name.chain_id);
+        if self.names.len() == MAX_TRUSTED_NAMES {
+            self.names.remove(0);
+        }
+        self.names.push(name);
+    }
+
+    pub fn get(&self, address: &[u8; ADDRRESS_BYTES_LEN], chain_id: u64) -> Option<&TrustedName> {
+        self.names
+            .iter()
+            .find(|n| &n.address == address && n.chain_id == chain_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(source: u8, name: &[u8]) -> Vec<u8> {
+        let mut data = alloc::vec![NAME_RECORD_TYPE];
+        data.extend_from_slice(&1029u32.to_be_bytes());
+        data.extend_from_slice(&[0x10; 20]);
+        data.push(source);
+        data.push(name.len() as u8);
+        data.extend_from_slice(name);
+        data
+    }
+
+    #[test]
+    fn parse_record() {
+        let mut data = record(0, b"alice.web3");
+        data.extend_from_slice(&[0x30, 0x44]);
+        let record = NameRecord::try_from(data.as_slice()).unwrap();
+        assert_eq!(
+            record.name,
+            TrustedName {
+                address: [0x10; 20],
+                chain_id: 1029,
+                name: "alice.web3".into(),
+                source: NameSource::Cns,
+            }
+        );
+        assert_eq!(record.signature, &[0x30, 0x44]);
+    }
+
+    #[test]
+    fn reject_malformed_record() {
+        // No signature
+        let data = record(0, b"alice.web3");
+        assert!(NameRecord::try_from(data.as_slice()).is_err());
+        // Unknown source
+        let mut data = record(2, b"alice.web3");
+        data.push(0x30);
+        assert!(NameRecord::try_from(data.as_slice()).is_err());
+        // Name too long
+        let mut data = record(0, &[b'a'; MAX_NAME_LEN + 1]);
+        data.push(0x30);
+        assert!(NameRecord::try_from(data.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,396 @@
+use super::transaction::{
+    decode_to, Transaction, TxType, ESPACE_TX_EIP1559_RLP_LEN, ESPACE_TX_EIP2930_RLP_LEN,
+    ESPACE_TX_LEGACY_RLP_LEN, ESPACE_TX_TYPE_1559, ESPACE_TX_TYPE_2930, TX_EIP1559_RLP_LEN,
+    TX_EIP2930_RLP_LEN, TX_LEGACY_RLP_LEN, TX_RLP_PREFIX_1559, TX_RLP_PREFIX_2930,
+};
+use super::{H256, U256};
+use crate::consts::MAX_TX_DATA_LEN;
+use crate::AppSW;
+use alloc::vec::Vec;
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
+use rlp_decoder::{Decodable, DecoderError, PayloadInfo, Rlp};
+
+// Longest RLP header: 1 byte + 8 bytes of length.
+const MAX_RLP_HEADER_LEN: usize = 9;
+
+// Longest scalar field (a 256-bit integer) with its header.
+const MAX_RLP_SCALAR_LEN: usize = 33;
+
+impl TxType {
+    fn item_count(&self) -> usize {
+        match self {
+            TxType::Legacy => TX_LEGACY_RLP_LEN,
+            TxType::Eip2930 => TX_EIP2930_RLP_LEN,
+            TxType::Eip1559 => TX_EIP1559_RLP_LEN,
+            TxType::EspaceLegacy => ESPACE_TX_LEGACY_RLP_LEN,
+            TxType::EspaceEip2930 => ESPACE_TX_EIP2930_RLP_LEN,
+            TxType::EspaceEip1559 => ESPACE_TX_EIP1559_RLP_LEN,
+        }
+    }
+
+    fn data_index(&self) -> usize {
+        match self {
+            TxType::Legacy => 8,
+            TxType::Eip2930 => 8,
+            TxType::Eip1559 => 9,
+            TxType::EspaceLegacy => 5,
+            TxType::EspaceEip2930 => 6,
+            TxType::EspaceEip1559 => 7,
+        }
+    }
+
+    fn access_list_index(&self) -> Option<usize> {
+        match self {
+            TxType::Legacy | TxType::EspaceLegacy => None,
+            TxType::Eip2930 => Some(9),
+            TxType::Eip1559 => Some(10),
+            TxType::EspaceEip2930 => Some(7),
+            TxType::EspaceEip1559 => Some(8),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemKind {
+    /// Small value kept with its header, decoded once the transaction is complete.
+    Scalar,
+    /// Transaction data, kept up to [`MAX_TX_DATA_LEN`] bytes.
+    Data,
+    /// Access list, only checked to be a list and skipped.
+    Skip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Prefix,
+    ListHeader,
+    ItemHeader,
+    ItemPayload { kind: ItemKind, remaining: usize },
+    Done,
+}
+
+/// Incremental RLP parser for Conflux transactions.
+///
+/// Chunks are fed as they are received, only the fields needed for display
+/// are kept, so the transaction length is not bounded by the available RAM.
+/// A parser handles either Core or eSpace transactions, see [`TxParser::new`].
+pub struct TxParser {
+    espace: bool,
+    state: State,
+    tx_type: Option<TxType>,
+    header: Vec<u8>,
+    list_remaining: usize,
+    items: Vec<Vec<u8>>,
+    data: Vec<u8>,
+    data_len: usize,
+    data_hasher: Keccak256,
+}
+
+impl Default for TxParser {
+    fn default() -> Self {
+        TxParser::new(false)
+    }
+}
+
+impl TxParser {
+    /// Creates a parser for eSpace transactions if `espace` is set, for Core
+    /// transactions otherwise.
+    pub fn new(espace: bool) -> Self {
+        TxParser {
+            espace,
+            state: State::Prefix,
+            tx_type: None,
+            header: Vec::new(),
+            list_remaining: 0,
+            items: Vec::new(),
+            data: Vec::new(),
+            data_len: 0,
+            data_hasher: Keccak256::new(),
+        }
+    }
+
+    pub fn is_espace(&self) -> bool {
+        self.espace
+    }
+
+    /// Parses a chunk of the RLP encoded transaction.
+    pub fn feed(&mut self, mut chunk: &[u8]) -> Result<(), AppSW> {
+        while let Some(&byte) = chunk.first() {
+            match self.state {
+                // eSpace typed transactions start with their type byte
+                State::Prefix if self.espace => {
+                    self.tx_type = Some(match byte {
+                        0xc0..=0xff => TxType::EspaceLegacy,
+                        ESPACE_TX_TYPE_2930 => TxType::EspaceEip2930,
+                        ESPACE_TX_TYPE_1559 => TxType::EspaceEip1559,
+                        _ => return Err(AppSW::TxParsingFail),
+                    });
+                    if byte < 0xc0 {
+                        chunk = &chunk[1..];
+                    }
+                    self.state = State::ListHeader;
+                }
+                State::Prefix => {
+                    // Legacy transactions are a bare RLP list
+                    if self.header.is_empty() && byte >= 0xc0 {
+                        self.tx_type = Some(TxType::Legacy);
+                        self.state = State::ListHeader;
+                        continue;
+                    }
+                    self.header.push(byte);
+                    chunk = &chunk[1..];
+                    if self.header.len() == TX_RLP_PREFIX_2930.len() {
+                        self.tx_type = match self.header.as_slice() {
+                            p if p == TX_RLP_PREFIX_2930 => Some(TxType::Eip2930),
+                            p if p == TX_RLP_PREFIX_1559 => Some(TxType::Eip1559),
+                            _ => return Err(AppSW::TxParsingFail),
+                        };
+                        self.header.clear();
+                        self.state = State::ListHeader;
+                    }
+                }
+                State::ListHeader => {
+                    self.header.push(byte);
+                    chunk = &chunk[1..];
+                    if let Some(info) = self.header_info()? {
+                        if self.header[0] < 0xc0 {
+                            return Err(AppSW::TxParsingFail);
+                        }
+                        self.header.clear();
+                        self.list_remaining = info.value_len;
+                        self.state = State::ItemHeader;
+                    }
+                }
+                State::ItemHeader => {
+                    self.consume_list(1)?;
+                    self.header.push(byte);
+                    chunk = &chunk[1..];
+                    if let Some(info) = self.header_info()? {
+                        self.start_item(info)?;
+                    }
+                }
+                State::ItemPayload { kind, remaining } => {
+                    let n = remaining.min(chunk.len());
+                    self.consume_list(n)?;
+                    let (payload, rest) = chunk.split_at(n);
+                    chunk = rest;
+                    match kind {
+                        ItemKind::Scalar => self.items.last_mut().unwrap().extend(payload),
+                        ItemKind::Data => {
+                            self.data_hasher
+                                .update(payload)
+                                .map_err(|_| AppSW::TxHashFail)?;
+                            let kept = (MAX_TX_DATA_LEN - self.data.len()).min(payload.len());
+                            self.data.extend(&payload[..kept]);
+                        }
+                        ItemKind::Skip => (),
+                    }
+                    if n == remaining {
+                        self.finish_item();
+                    } else {
+                        self.state = State::ItemPayload {
+                            kind,
+                            remaining: remaining - n,
+                        };
+                    }
+                }
+                // Trailing bytes after the transaction
+                State::Done => return Err(AppSW::TxParsingFail),
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds the transaction once every chunk has been fed.
+    pub fn finish(&mut self) -> Result<Transaction, AppSW> {
+        let tx_type = self.tx_type.ok_or(AppSW::TxParsingFail)?;
+        if self.state != State::Done || self.items.len() != tx_type.item_count() {
+            return Err(AppSW::TxParsingFail);
+        }
+        let mut data_hash = H256::default();
+        self.data_hasher
+            .finalize(&mut data_hash.0)
+            .map_err(|_| AppSW::TxHashFail)?;
+        self.build(tx_type, data_hash)
+            .map_err(|_| AppSW::TxParsingFail)
+    }
+
+    fn build(&mut self, tx_type: TxType, data_hash: H256) -> Result<Transaction, DecoderError> {
+        // The parser is done with the data, move it rather than copying it
+        // on the small heap
+        let data = core::mem::take(&mut self.data);
+        let tx = match tx_type {
+            TxType::Legacy | TxType::Eip2930 => Transaction {
+                nonce: self.val_at(0)?,
+                gas_price: Some(self.val_at(1)?),
+                gas: self.val_at(2)?,
+                to: decode_to(&Rlp::new(&self.items[3]))?,
+                value: self.val_at(4)?,
+                storage_limit: self.val_at(5)?,
+                epoch_height: self.val_at(6)?,
+                chain_id: self.val_at(7)?,
+                data,
+                data_len: self.data_len,
+                data_hash,
+                // The access list is not displayed
+                access_list: None,
+                max_priority_fee_per_gas: None,
+                max_fee_per_gas: None,
+                tx_type,
+            },
+            TxType::Eip1559 => Transaction {
+                nonce: self.val_at(0)?,
+                gas_price: None,
+                max_priority_fee_per_gas: Some(self.val_at(1)?),
+                max_fee_per_gas: Some(self.val_at(2)?),
+                gas: self.val_at(3)?,
+                to: decode_to(&Rlp::new(&self.items[4]))?,
+                value: self.val_at(5)?,
+                storage_limit: self.val_at(6)?,
+                epoch_height: self.val_at(7)?,
+                chain_id: self.val_at(8)?,
+                data,
+                data_len: self.data_len,
+                data_hash,
+                access_list: None,
+                tx_type,
+            },
+            TxType::EspaceLegacy => {
+                // EIP-155 signing payloads end with empty r and s
+                if self.val_at::<U256>(7)? != U256::zero()
+                    || self.val_at::<U256>(8)? != U256::zero()
+                {
+                    return Err(DecoderError::Custom("invalid EIP-155 transaction"));
+                }
+                Transaction {
+                    nonce: self.val_at(0)?,
+                    gas_price: Some(self.val_at(1)?),
+                    gas: self.val_at(2)?,
+                    to: decode_to(&Rlp::new(&self.items[3]))?,
+                    value: self.val_at(4)?,
+                    chain_id: self.val_at(6)?,
+                    data,
+                    data_len: self.data_len,
+                    data_hash,
+                    tx_type,
+                    ..Default::default()
+                }
+            }
+            TxType::EspaceEip2930 => Transaction {
+                chain_id: self.val_at(0)?,
+                nonce: self.val_at(1)?,
+                gas_price: Some(self.val_at(2)?),
+                gas: self.val_at(3)?,
+                to: decode_to(&Rlp::new(&self.items[4]))?,
+                value: self.val_at(5)?,
+                data,
+                data_len: self.data_len,
+                data_hash,
+                tx_type,
+                ..Default::default()
+            },
+            TxType::EspaceEip1559 => Transaction {
+                chain_id: self.val_at(0)?,
+                nonce: self.val_at(1)?,
+                max_priority_fee_per_gas: Some(self.val_at(2)?),
+                max_fee_per_gas: Some(self.val_at(3)?),
+                gas: self.val_at(4)?,
+                to: decode_to(&Rlp::new(&self.items[5]))?,
+                value: self.val_at(6)?,
+                data,
+                data_len: self.data_len,
+                data_hash,
+                tx_type,
+                ..Default::default()
+            },
+        };
+        Ok(tx)
+    }
+
+    fn val_at<T: Decodable>(&self, index: usize) -> Result<T, DecoderError> {
+        Rlp::new(&self.items[index]).as_val()
+    }
+
+    // Payload info of the pending header, `None` if more bytes are needed.
+    fn header_info(&self) -> Result<Option<PayloadInfo>, AppSW> {
+        match PayloadInfo::from(&self.header) {
+            Ok(info) => Ok(Some(info)),
+            Err(DecoderError::RlpIsTooShort) if self.header.len() < MAX_RLP_HEADER_LEN => Ok(None),
+            Err(_) => Err(AppSW::TxParsingFail),
+        }
+    }
+
+    fn consume_list(&mut self, n: usize) -> Result<(), AppSW> {
+        self.list_remaining = self
+            .list_remaining
+            .checked_sub(n)
+            .ok_or(AppSW::TxParsingFail)?;
+        Ok(())
+    }
+
+    fn start_item(&mut self, info: PayloadInfo) -> Result<(), AppSW> {
+        let tx_type = self.tx_type.ok_or(AppSW::TxParsingFail)?;
+        let index = self.items.len();
+        if index >= tx_type.item_count() {
+            return Err(AppSW::TxParsingFail);
+        }
+        let is_list = self.header[0] >= 0xc0;
+
+        let kind = if index == tx_type.data_index() {
+            ItemKind::Data
+        } else if Some(index) == tx_type.access_list_index() {
+            ItemKind::Skip
+        } else {
+            ItemKind::Scalar
+        };
+        match kind {
+            ItemKind::Skip if !is_list => return Err(AppSW::TxParsingFail),
+            ItemKind::Scalar | ItemKind::Data if is_list => return Err(AppSW::TxParsingFail),
+            ItemKind::Scalar if info.total() > MAX_RLP_SCALAR_LEN => {
+                return Err(AppSW::TxParsingFail)
+            }
+            _ => (),
+        }
+
+        self.items.push(match kind {
+            ItemKind::Scalar => self.header.clone(),
+            _ => Vec::new(),
+        });
+
+        // Single byte values are their own header
+        if info.header_len == 0 {
+            if kind == ItemKind::Data {
+                self.data_hasher
+                    .update(&self.header)
+                    .map_err(|_| AppSW::TxHashFail)?;
+                self.data.push(self.header[0]);
+                self.data_len = 1;
+            }
+            self.header.clear();
+            self.finish_item();
+            return Ok(());
+        }
+
+        if kind == ItemKind::Data {
+            self.data_len = info.value_len;
+        }
+        self.header.clear();
+        if info.value_len == 0 {
+            self.finish_item();
+        } else {
+            self.state = State::ItemPayload {
+                kind,
+                remaining: info.value_len,
+            };
+        }
+        Ok(())
+    }
+
+    fn finish_item(&mut self) {
+        self.state = if self.list_remaining == 0 {
+            State::Done
+        } else {
+            State::ItemHeader
+        };
+    }
+}
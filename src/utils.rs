@@ -1,5 +1,7 @@
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
+use ledger_device_sdk::hash::{sha3::Keccak256, HashInit};
 
+use crate::consts::{ADDRRESS_BYTES_LEN, HASH_BYTES_LEN};
 use crate::AppSW;
 
 /// BIP32 path stored as an array of [`u32`].
@@ -40,3 +42,46 @@ impl TryFrom<&[u8]> for Bip32Path {
         ))
     }
 }
+
+/// Formats an eSpace address as `0x` followed by its EIP-55 mixed-case
+/// checksummed hex encoding.
+///
+/// # Arguments
+///
+/// * `raw` - 20-byte address
+pub fn eip55_encode(raw: &[u8]) -> Result<String, AppSW> {
+    let lower = hex::encode(raw);
+    let mut hash = [0u8; 32];
+    Keccak256::new()
+        .hash(lower.as_bytes(), &mut hash)
+        .map_err(|_| AppSW::AddrDisplayFail)?;
+
+    // A letter is uppercased when the matching nibble of the hash is >= 8
+    let mut checksummed = String::with_capacity(2 + lower.len());
+    checksummed.push_str("0x");
+    for (i, c) in lower.chars().enumerate() {
+        let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+        if nibble >= 8 {
+            checksummed.push(c.to_ascii_uppercase());
+        } else {
+            checksummed.push(c);
+        }
+    }
+    Ok(checksummed)
+}
+
+/// Computes the eSpace address a Core address is mapped to, as used by the
+/// `CrossSpaceCall` internal contract: the last 20 bytes of its Keccak256 hash.
+///
+/// # Arguments
+///
+/// * `core_addr` - 20-byte Core address
+pub fn espace_mapped_address(core_addr: &[u8]) -> Result<[u8; ADDRRESS_BYTES_LEN], AppSW> {
+    let mut hash = [0u8; HASH_BYTES_LEN];
+    Keccak256::new()
+        .hash(core_addr, &mut hash)
+        .map_err(|_| AppSW::AddrDisplayFail)?;
+    Ok(hash[HASH_BYTES_LEN - ADDRRESS_BYTES_LEN..]
+        .try_into()
+        .unwrap())
+}
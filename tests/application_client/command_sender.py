@@ -25,6 +25,10 @@ class P2(IntEnum):
     P2_MORE = 0x80
     # Parameter 2 for true.
     P2_TRUE = 0x01
+    # Parameter 2 flag for the eSpace address in GET_PUBLIC_KEY.
+    P2_ESPACE = 0x02
+    # Parameter 2 flag for the mapped eSpace address in GET_PUBLIC_KEY.
+    P2_MAPPED = 0x04
 
 class InsType(IntEnum):
     GET_VERSION    = 0x01
@@ -32,9 +36,19 @@ class InsType(IntEnum):
     SIGN_TX        = 0x03
     PERSONAL_SIGN  = 0x04
     GET_APP_NAME   = 0x05
+    SIGN_TYPED_DATA = 0x06
+    CIP23_STRUCT_DEF = 0x07
+    CIP23_STRUCT_IMPL = 0x08
+    SIGN_ESPACE_TX = 0x09
+    VERIFY_ADDRESS = 0x0A
+    PROVIDE_TOKEN_INFO = 0x0B
+    PROVIDE_CALL_DESCRIPTOR = 0x0C
+    PROVIDE_TRUSTED_NAME = 0x0D
+    SAVE_CONTACT = 0x0E
 
 class Errors(IntEnum):
     SW_DENY                    = 0x6985
+    SW_INVALID_DATA            = 0x6A80
     SW_WRONG_P1P2              = 0x6A86
     SW_INS_NOT_SUPPORTED       = 0x6D00
     SW_CLA_NOT_SUPPORTED       = 0x6E00
@@ -48,6 +62,8 @@ class Errors(IntEnum):
     SW_TX_HASH_FAIL            = 0xB006
     SW_BAD_STATE               = 0xB007
     SW_SIGNATURE_FAIL          = 0xB008
+    SW_ADDRESS_MISMATCH        = 0xB00B
+    SW_ADDRESS_BOOK_FULL       = 0xB00C
 
 
 def split_message(message: bytes, max_size: int) -> List[bytes]:
@@ -91,6 +107,22 @@ class ConfluxCommandSender:
                                      data=pack_derivation_path(path))
 
 
+    def get_espace_public_key(self, path: str) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.GET_PUBLIC_KEY,
+                                     p1=P1.P1_START,
+                                     p2=P2.P2_TRUE | P2.P2_ESPACE,
+                                     data=pack_derivation_path(path))
+
+
+    def get_mapped_public_key(self, path: str) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.GET_PUBLIC_KEY,
+                                     p1=P1.P1_START,
+                                     p2=P2.P2_TRUE | P2.P2_MAPPED,
+                                     data=pack_derivation_path(path))
+
+
     @contextmanager
     def get_public_key_with_confirmation(self,
                                          path: str,
@@ -107,9 +139,13 @@ class ConfluxCommandSender:
 
 
     @contextmanager
-    def sign_tx(self, path: str, transaction: bytes) -> Generator[None, None, None]:
+    def sign_tx(self,
+                path: str,
+                transaction: bytes,
+                ins: InsType = InsType.SIGN_TX
+                ) -> Generator[None, None, None]:
         self.backend.exchange(cla=CLA,
-                              ins=InsType.SIGN_TX,
+                              ins=ins,
                               p1=P1.P1_START,
                               p2=P2.P2_MORE,
                               data=pack_derivation_path(path))
@@ -118,19 +154,25 @@ class ConfluxCommandSender:
 
         for msg in messages[:-1]:
             self.backend.exchange(cla=CLA,
-                                  ins=InsType.SIGN_TX,
+                                  ins=ins,
                                   p1=idx,
                                   p2=P2.P2_MORE,
                                   data=msg)
-            idx += 1
+            # The data block index wraps around after 0xFF
+            idx = idx % 0xFF + 1
 
         with self.backend.exchange_async(cla=CLA,
-                                         ins=InsType.SIGN_TX,
+                                         ins=ins,
                                          p1=idx,
                                          p2=P2.P2_LAST,
                                          data=messages[-1]) as response:
             yield response
 
+    @contextmanager
+    def sign_espace_tx(self, path: str, transaction: bytes) -> Generator[None, None, None]:
+        with self.sign_tx(path, transaction, InsType.SIGN_ESPACE_TX) as response:
+            yield response
+
     @contextmanager
     def personal_sign(self, path: str, data: bytes) -> Generator[None, None, None]:
         self.backend.exchange(cla=CLA,
@@ -156,5 +198,89 @@ class ConfluxCommandSender:
                                          data=messages[-1]) as response:
             yield response
 
+    @contextmanager
+    def sign_typed_data_hashed(self,
+                               path: str,
+                               domain_hash: bytes,
+                               message_hash: bytes
+                               ) -> Generator[None, None, None]:
+        with self.backend.exchange_async(cla=CLA,
+                                         ins=InsType.SIGN_TYPED_DATA,
+                                         p1=P1.P1_START,
+                                         p2=P2.P2_LAST,
+                                         data=pack_derivation_path(path) + domain_hash + message_hash,
+                                         ) as response:
+            yield response
+
+    def cip23_struct_def(self, p2: int, data: bytes) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.CIP23_STRUCT_DEF,
+                                     p1=P1.P1_START,
+                                     p2=p2,
+                                     data=data)
+
+
+    def cip23_struct_impl(self, p2: int, data: bytes) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.CIP23_STRUCT_IMPL,
+                                     p1=P1.P1_START,
+                                     p2=p2,
+                                     data=data)
+
+
+    @contextmanager
+    def sign_typed_data(self, path: str) -> Generator[None, None, None]:
+        with self.backend.exchange_async(cla=CLA,
+                                         ins=InsType.SIGN_TYPED_DATA,
+                                         p1=P1.P1_START,
+                                         p2=P2.P2_TRUE,
+                                         data=pack_derivation_path(path),
+                                         ) as response:
+            yield response
+
+    def provide_token_info(self, record: bytes) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.PROVIDE_TOKEN_INFO,
+                                     p1=P1.P1_START,
+                                     p2=P2.P2_LAST,
+                                     data=record)
+
+
+    def provide_call_descriptor(self, record: bytes) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.PROVIDE_CALL_DESCRIPTOR,
+                                     p1=P1.P1_START,
+                                     p2=P2.P2_LAST,
+                                     data=record)
+
+
+    def provide_trusted_name(self, record: bytes) -> RAPDU:
+        return self.backend.exchange(cla=CLA,
+                                     ins=InsType.PROVIDE_TRUSTED_NAME,
+                                     p1=P1.P1_START,
+                                     p2=P2.P2_LAST,
+                                     data=record)
+
+
+    @contextmanager
+    def verify_address(self, path: str, address: str) -> Generator[None, None, None]:
+        with self.backend.exchange_async(cla=CLA,
+                                         ins=InsType.VERIFY_ADDRESS,
+                                         p1=P1.P1_START,
+                                         p2=P2.P2_LAST,
+                                         data=pack_derivation_path(path) + address.encode("ascii"),
+                                         ) as response:
+            yield response
+
+    @contextmanager
+    def save_contact(self, label: str) -> Generator[None, None, None]:
+        with self.backend.exchange_async(cla=CLA,
+                                         ins=InsType.SAVE_CONTACT,
+                                         p1=P1.P1_START,
+                                         p2=P2.P2_LAST,
+                                         data=label.encode("ascii"),
+                                         ) as response:
+            yield response
+
     def get_async_response(self) -> Optional[RAPDU]:
         return self.backend.last_async_response
@@ -57,6 +57,24 @@ def unpack_get_public_key_response(response: bytes) -> Tuple[int, bytes, int, by
     assert len(response) == 0
     return pub_key_len, pub_key, chain_code_len, chain_code
 
+# Unpack from response:
+# response = pub_key_len (1)
+#            pub_key (var)
+#            chain_code_len (1)
+#            chain_code (var)
+#            address_len (1)
+#            address (var)
+def unpack_get_espace_public_key_response(response: bytes) -> Tuple[bytes, bytes, bytes]:
+    response, pub_key_len, pub_key = pop_size_prefixed_buf_from_buf(response)
+    response, chain_code_len, chain_code = pop_size_prefixed_buf_from_buf(response)
+    response, address_len, address = pop_size_prefixed_buf_from_buf(response)
+
+    assert pub_key_len == 65
+    assert chain_code_len == 32
+    assert address_len == 20
+    assert len(response) == 0
+    return pub_key, chain_code, address
+
 # Unpack from response:
 # response = der_sig_len (1)
 #            der_sig (var)
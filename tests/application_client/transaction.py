@@ -1,3 +1,4 @@
+import rlp
 from cfx_utils.types import TxParam
 from cfx_account.transactions.transactions import Transaction as CfxTransaction
 
@@ -13,3 +14,43 @@ class Transaction:
 
     def get_internal_tx(self) -> int:
         return 1
+
+
+class EspaceTransaction:
+    # Legacy transactions are serialized for EIP-155 signing, EIP-2930 and
+    # EIP-1559 ones (tx_type 1 and 2) with their type prefix
+    def __init__(self,
+                 tx_type: int,
+                 chain_id: int,
+                 nonce: int,
+                 gas: int,
+                 to: bytes,
+                 value: int,
+                 data: bytes = b"",
+                 gas_price: int = 0,
+                 max_priority_fee_per_gas: int = 0,
+                 max_fee_per_gas: int = 0):
+        self.tx_type = tx_type
+        self.chain_id = chain_id
+        self.nonce = nonce
+        self.gas = gas
+        self.to = to
+        self.value = value
+        self.data = data
+        self.gas_price = gas_price
+        self.max_priority_fee_per_gas = max_priority_fee_per_gas
+        self.max_fee_per_gas = max_fee_per_gas
+
+    def serialize(self) -> bytes:
+        if self.tx_type == 0:
+            return rlp.encode([self.nonce, self.gas_price, self.gas, self.to, self.value,
+                               self.data, self.chain_id, 0, 0])
+        if self.tx_type == 1:
+            fields = [self.chain_id, self.nonce, self.gas_price, self.gas, self.to,
+                      self.value, self.data, []]
+        elif self.tx_type == 2:
+            fields = [self.chain_id, self.nonce, self.max_priority_fee_per_gas,
+                      self.max_fee_per_gas, self.gas, self.to, self.value, self.data, []]
+        else:
+            raise TransactionError(f"Unknown transaction type {self.tx_type}")
+        return bytes([self.tx_type]) + rlp.encode(fields)
@@ -0,0 +1,34 @@
+import pytest
+
+from ragger.error import ExceptionRAPDU
+from application_client.command_sender import ConfluxCommandSender, Errors
+from utils import sign_call_descriptor
+
+CONTRACT = bytes.fromhex("8d7df9316faa0586e175b5e6d03c6bda76e3d950")
+# deposit(uint256,address)
+SELECTOR = bytes.fromhex("6e553f65")
+PARAMS = [
+    (0x00, 0x03, 0x01, "Amount"),
+    (0x01, 0x00, None, "Token"),
+]
+
+
+# In this test we check that a descriptor signed by the test signer is accepted
+def test_provide_call_descriptor(backend):
+    client = ConfluxCommandSender(backend)
+    record = sign_call_descriptor(CONTRACT, 1029, SELECTOR, "deposit tokens", PARAMS)
+
+    response = client.provide_call_descriptor(record)
+    assert response.status == 0x9000
+
+
+# In this test we check that a descriptor altered after signing is rejected
+def test_provide_call_descriptor_bad_signature(backend):
+    client = ConfluxCommandSender(backend)
+    record = bytearray(sign_call_descriptor(CONTRACT, 1029, SELECTOR, "deposit tokens", PARAMS))
+    # Change the selector
+    record[1 + 4 + 20] ^= 0xff
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_call_descriptor(bytes(record))
+    assert e.value.status == Errors.SW_INVALID_DATA
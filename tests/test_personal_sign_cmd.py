@@ -0,0 +1,37 @@
+from application_client.command_sender import ConfluxCommandSender
+from application_client.response_unpacker import unpack_get_public_key_response, unpack_vrs_response
+from utils import check_rs_prefix_msg_signature_validity
+
+
+# In this test a printable ASCII message is displayed as is, then signed
+def test_personal_sign_ascii(backend, scenario_navigator):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    message = b"Sign in to Conflux Scan\nNonce: 42"
+    with client.personal_sign(path=path, data=message):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_prefix_msg_signature_validity(public_key, sig, message)
+
+
+# In this test a binary message is displayed in hex, then signed
+def test_personal_sign_binary(backend, scenario_navigator):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    message = bytes.fromhex("00ff10c3a9e4b8ad01")
+    with client.personal_sign(path=path, data=message):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_prefix_msg_signature_validity(public_key, sig, message)
@@ -1,9 +1,10 @@
 import pytest
 
 from application_client.command_sender import ConfluxCommandSender, Errors
-from application_client.response_unpacker import unpack_get_public_key_response
+from application_client.response_unpacker import unpack_get_public_key_response, unpack_get_espace_public_key_response
 from ragger.bip import calculate_public_key_and_chaincode, CurveChoice
 from ragger.error import ExceptionRAPDU
+from eth_utils import keccak
 
 
 # In this test we check that the GET_PUBLIC_KEY works in non-confirmation mode
@@ -17,6 +18,30 @@ def test_get_public_key_no_confirm(backend):
         assert public_key.hex() == ref_public_key
 
 
+# In this test we check that the GET_PUBLIC_KEY returns the unmodified eSpace address
+def test_get_espace_public_key_no_confirm(backend):
+    for path in ["m/44'/60'/0'/0/0", "m/44'/503'/0'/0/0"]:
+        client = ConfluxCommandSender(backend)
+        response = client.get_espace_public_key(path=path).data
+        public_key, _, address = unpack_get_espace_public_key_response(response)
+
+        ref_public_key, _ = calculate_public_key_and_chaincode(CurveChoice.Secp256k1, path=path)
+        assert public_key.hex() == ref_public_key
+        assert address == keccak(public_key[1:])[-20:]
+
+
+# In this test we check that the GET_PUBLIC_KEY returns the eSpace address mapped from the Core address
+def test_get_mapped_public_key_no_confirm(backend):
+    path = "m/44'/503'/0'/0/0"
+    client = ConfluxCommandSender(backend)
+    response = client.get_mapped_public_key(path=path).data
+    public_key, _, mapped_address = unpack_get_espace_public_key_response(response)
+
+    core_address = bytearray(keccak(public_key[1:])[-20:])
+    core_address[0] = (core_address[0] & 0x0f) | 0x10
+    assert mapped_address == keccak(bytes(core_address))[-20:]
+
+
 # In this test we check that the GET_PUBLIC_KEY works in confirmation mode
 def test_get_public_key_confirm_accepted(backend, scenario_navigator):
     client = ConfluxCommandSender(backend)
@@ -44,3 +69,28 @@ def test_get_public_key_confirm_refused(backend, scenario_navigator):
     # Assert that we have received a refusal
     assert e.value.status == Errors.SW_DENY
     assert len(e.value.data) == 0
+
+
+# In this test we check that VERIFY_ADDRESS rejects a malformed address before any display
+def test_verify_address_invalid(backend):
+    client = ConfluxCommandSender(backend)
+    path = "m/44'/503'/0'/0/0"
+
+    # Last character changed, the checksum does not match anymore
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.verify_address(path=path, address="cfx:aajg4wt2mbmbb44sp6szd783ry0jtad5bea80xdy7r"):
+            pass
+
+    assert e.value.status == Errors.SW_INVALID_DATA
+
+
+# In this test we check that an address that is not a user address is rejected
+def test_verify_address_contract(backend):
+    client = ConfluxCommandSender(backend)
+    path = "m/44'/503'/0'/0/0"
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.verify_address(path=path, address="cfx:acag4wt2mbmbb44sp6szd783ry0jtad5bex25t8vc9"):
+            pass
+
+    assert e.value.status == Errors.SW_INVALID_DATA
@@ -0,0 +1,123 @@
+import pytest
+
+from ragger.error import ExceptionRAPDU
+from ragger.navigator import NavIns, NavInsID
+from application_client.transaction import Transaction
+from application_client.command_sender import ConfluxCommandSender, Errors
+from application_client.response_unpacker import unpack_get_public_key_response, unpack_vrs_response
+from utils import ROOT_SCREENSHOT_PATH, check_rs_signature_validity
+from cfx_address import Base32Address
+
+# Receiver of the token transfer, used by no other test as contacts are kept
+# in NVM across tests
+RECIPIENT = "0x1999999999999999999999999999999999999999"
+
+TOKEN_ADDRESS = "0x8123456789012345678901234567890123456789"
+
+MAIN_NET_ID = 1029
+
+
+# In this test we check that a contact cannot be saved before a transaction is approved
+def test_save_contact_without_transaction(backend):
+    client = ConfluxCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.save_contact("Treasury"):
+            pass
+    assert e.value.status == Errors.SW_BAD_STATE
+
+
+# In this test we check that labels which cannot be displayed are rejected
+@pytest.mark.parametrize("label", ["", "A" * 21, "line\nbreak"])
+def test_save_contact_invalid_label(backend, label):
+    client = ConfluxCommandSender(backend)
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.save_contact(label):
+            pass
+    assert e.value.status == Errors.SW_INVALID_DATA
+
+
+def sign(client: ConfluxCommandSender, scenario_navigator, public_key: bytes, test_name: str,
+         **kwargs) -> None:
+    path: str = "m/44'/503'/0'/0/0"
+    transaction = Transaction(
+        nonce=1,
+        gas=1,
+        gasPrice=1,
+        storageLimit=1,
+        epochHeight=1,
+        chainId=MAIN_NET_ID,
+        **kwargs
+    ).serialize()
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve(test_name=test_name)
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_signature_validity(public_key, sig, transaction)
+
+
+# In this test the receiver of a token transfer is saved as a contact, then its
+# label is displayed in the review of a payment to the same address. The
+# contact is then listed in the settings and removed.
+def test_save_contact_and_sign(backend, scenario_navigator, firmware, navigator, test_name):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    # transfer(address,uint256), the recipient being the receiver, not the token
+    transfer = (bytes.fromhex("a9059cbb") + bytes.fromhex(RECIPIENT[2:]).rjust(32, b"\x00")
+                + (5).to_bytes(32, "big"))
+    sign(client, scenario_navigator, public_key, test_name + "_transfer",
+         to=Base32Address(TOKEN_ADDRESS, network_id=MAIN_NET_ID), value=0, data=transfer)
+
+    with client.save_contact("Treasury"):
+        scenario_navigator.address_review_approve(test_name=test_name + "_save")
+        if firmware.device.startswith("nano"):
+            # Dismiss the confirmation
+            navigator.navigate([NavInsID.BOTH_CLICK],
+                               screen_change_after_last_instruction=False)
+    assert client.get_async_response().status == 0x9000
+
+    # The contact can only be saved once
+    with pytest.raises(ExceptionRAPDU) as e:
+        with client.save_contact("Treasury"):
+            pass
+    assert e.value.status == Errors.SW_BAD_STATE
+
+    sign(client, scenario_navigator, public_key, test_name + "_payment",
+         to=Base32Address(RECIPIENT, network_id=MAIN_NET_ID), value=1, data=b"")
+
+    # The contact is listed in the settings and removed, which also keeps the
+    # address book empty for the other tests
+    if firmware.device.startswith("nano"):
+        # Settings, then Address book, then the contact
+        instructions = [
+            NavInsID.RIGHT_CLICK,
+            NavInsID.RIGHT_CLICK,
+            NavInsID.BOTH_CLICK,
+            NavInsID.RIGHT_CLICK,
+            NavInsID.BOTH_CLICK,
+            NavInsID.BOTH_CLICK,
+        ]
+        navigator.navigate_and_compare(ROOT_SCREENSHOT_PATH, test_name + "_menu", instructions,
+                                       screen_change_before_first_instruction=False)
+        navigator.navigate_until_text_and_compare(NavInsID.RIGHT_CLICK,
+                                                  [NavInsID.BOTH_CLICK],
+                                                  "Remove",
+                                                  ROOT_SCREENSHOT_PATH,
+                                                  test_name + "_remove")
+    else:
+        # Third settings switch, then the removal of the contact
+        instructions = [
+            NavInsID.USE_CASE_HOME_SETTINGS,
+            NavIns(NavInsID.TOUCH, (200, 473)),
+            NavInsID.USE_CASE_CHOICE_CONFIRM,
+            NavInsID.USE_CASE_SUB_SETTINGS_EXIT,
+        ]
+        navigator.navigate_and_compare(ROOT_SCREENSHOT_PATH, test_name + "_menu", instructions,
+                                       screen_change_before_first_instruction=False)
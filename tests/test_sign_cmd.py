@@ -20,6 +20,7 @@ MAIN_NET_ID = 1029
 # stax, flex setting option location
 # first setting option (200, 113)
 # second setting option (200, 293)
+# third setting option (200, 473)
 
 # In these tests we check the behavior of the device when asked to sign a transaction
 
@@ -388,6 +389,130 @@ def test_sign_tx_long_tx(backend, scenario_navigator, firmware, navigator):
     assert check_rs_signature_validity(public_key, sig, transaction)
 
 
+# In this test a transaction larger than three APDUs (765 bytes) is signed, its
+# data being sent in several chunks while still being displayed in full
+def test_sign_tx_multi_chunk_tx(backend, scenario_navigator, firmware, navigator):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    transaction = Transaction(
+        to=Base32Address(TARGET_ADDRESS, network_id=MAIN_NET_ID),
+        value=1,
+        nonce=1,
+        gas=1,
+        gasPrice=1,
+        storageLimit=1,
+        epochHeight=1,
+        chainId=MAIN_NET_ID,
+        data=bytes(range(256)) * 3 + bytes(range(200))
+    ).serialize()
+    assert len(transaction) > 3 * 255
+
+    # Enable display of transaction data (NBGL devices only)
+    if not firmware.device.startswith("nano"):
+        navigator.navigate([NavInsID.USE_CASE_HOME_SETTINGS,
+                            NavIns(NavInsID.TOUCH, (200, 293)),
+                            NavInsID.USE_CASE_SUB_SETTINGS_EXIT],
+                            screen_change_before_first_instruction=False,
+                            screen_change_after_last_instruction=False)
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_signature_validity(public_key, sig, transaction)
+
+
+# In this test the transaction is sent in more than 255 data blocks, so the
+# data block index wraps around from 0xFF to 0x01. Only the first 1024 bytes
+# of the data are displayed.
+def test_sign_tx_wrapped_chunk_index(backend, scenario_navigator, firmware, navigator):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    transaction = Transaction(
+        to=Base32Address(TARGET_ADDRESS, network_id=MAIN_NET_ID),
+        value=1,
+        nonce=1,
+        gas=1,
+        gasPrice=1,
+        storageLimit=1,
+        epochHeight=1,
+        chainId=MAIN_NET_ID,
+        data=bytes(range(256)) * 260
+    ).serialize()
+    assert len(transaction) > 256 * 255
+
+    # Enable display of transaction data (NBGL devices only)
+    if not firmware.device.startswith("nano"):
+        navigator.navigate([NavInsID.USE_CASE_HOME_SETTINGS,
+                            NavIns(NavInsID.TOUCH, (200, 293)),
+                            NavInsID.USE_CASE_SUB_SETTINGS_EXIT],
+                            screen_change_before_first_instruction=False,
+                            screen_change_after_last_instruction=False)
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_signature_validity(public_key, sig, transaction)
+
+# In this test a contract deployment is signed: as it is blind-signed, blind
+# signing is enabled first, then the init code size and hash are reviewed
+def test_sign_tx_deploy(backend, scenario_navigator, firmware, navigator):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    init_code = bytes.fromhex("6080604052348015600f57600080fd5b50603f80601d6000396000f3fe"
+                              "6080604052600080fdfea164736f6c6343000812000a")
+    transaction = Transaction(
+        value=0,
+        nonce=1,
+        gas=1,
+        gasPrice=1,
+        storageLimit=1,
+        epochHeight=1,
+        chainId=MAIN_NET_ID,
+        data=init_code
+    ).serialize()
+
+    # Enable blind signing
+    if firmware.device.startswith("nano"):
+        navigator.navigate([NavInsID.RIGHT_CLICK,
+                            NavInsID.RIGHT_CLICK,
+                            NavInsID.BOTH_CLICK,
+                            NavInsID.BOTH_CLICK,
+                            NavInsID.RIGHT_CLICK,
+                            NavInsID.RIGHT_CLICK,
+                            NavInsID.BOTH_CLICK],
+                            screen_change_before_first_instruction=False,
+                            screen_change_after_last_instruction=False)
+    else:
+        navigator.navigate([NavInsID.USE_CASE_HOME_SETTINGS,
+                            NavIns(NavInsID.TOUCH, (200, 113)),
+                            NavInsID.USE_CASE_SUB_SETTINGS_EXIT],
+                            screen_change_before_first_instruction=False,
+                            screen_change_after_last_instruction=False)
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_signature_validity(public_key, sig, transaction)
+
+
 # Transaction signature refused test
 # The test will ask for a transaction signature that will be refused on screen
 def test_sign_tx_refused(backend, scenario_navigator):
@@ -0,0 +1,46 @@
+from application_client.transaction import Transaction
+from application_client.command_sender import ConfluxCommandSender
+from application_client.response_unpacker import unpack_get_public_key_response, unpack_vrs_response
+from utils import check_rs_signature_validity
+from web3 import Web3
+from cfx_address import Base32Address
+
+# CrossSpaceCall internal contract
+CROSS_SPACE_CALL = "0x0888000000000000000000000000000000000006"
+
+# eSpace receiver, displayed with its EIP-55 checksum
+ESPACE_ADDRESS = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed"
+
+MAIN_NET_ID = 1029
+
+
+# In this test CFX is sent to eSpace with transferEVM(bytes20): the review is
+# labelled "Send to eSpace" and shows the eSpace receiver in hex along with the
+# value of the transaction as the amount.
+def test_sign_cross_space_transfer(backend, scenario_navigator):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    # bytes20 arguments are right padded
+    data = bytes.fromhex("da8d5daf") + bytes.fromhex(ESPACE_ADDRESS[2:]).ljust(32, b"\x00")
+    transaction = Transaction(
+        to=Base32Address(CROSS_SPACE_CALL, network_id=MAIN_NET_ID),
+        value=Web3.to_wei(1, 'ether'),
+        nonce=1,
+        gas=1,
+        gasPrice=1,
+        storageLimit=1,
+        epochHeight=1,
+        chainId=MAIN_NET_ID,
+        data=data
+    ).serialize()
+
+    with client.sign_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    assert check_rs_signature_validity(public_key, sig, transaction)
@@ -0,0 +1,48 @@
+import pytest
+from sha3 import keccak_256
+
+from application_client.transaction import EspaceTransaction
+from application_client.command_sender import ConfluxCommandSender
+from application_client.response_unpacker import unpack_get_espace_public_key_response, unpack_vrs_response
+from utils import recover_espace_address
+from web3 import Web3
+
+TARGET_ADDRESS = bytes.fromhex("1123456789012345678901234567890123456789")
+
+ESPACE_CHAIN_ID = 1030
+
+
+# In these tests eSpace transactions of each type are signed with SIGN_ESPACE_TX,
+# then the signature is checked to recover the eSpace address of the device
+@pytest.mark.parametrize("tx_type", [0, 1, 2])
+def test_sign_espace_tx(backend, scenario_navigator, tx_type):
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/60'/0'/0/0"
+
+    response = client.get_espace_public_key(path=path).data
+    _, _, address = unpack_get_espace_public_key_response(response)
+
+    transaction = EspaceTransaction(
+        tx_type=tx_type,
+        chain_id=ESPACE_CHAIN_ID,
+        nonce=1,
+        gas=21000,
+        to=TARGET_ADDRESS,
+        value=Web3.to_wei(1, 'ether'),
+        gas_price=Web3.to_wei(20, 'gwei'),
+        max_priority_fee_per_gas=Web3.to_wei(1, 'gwei'),
+        max_fee_per_gas=Web3.to_wei(20, 'gwei'),
+    ).serialize()
+
+    with client.sign_espace_tx(path=path, transaction=transaction):
+        scenario_navigator.review_approve(test_name=f"test_sign_espace_tx_type{tx_type}")
+
+    response = client.get_async_response().data
+    _, sig, v = unpack_vrs_response(response)
+    if tx_type == 0:
+        # EIP-155 v, truncated to one byte
+        parity = (v - (ESPACE_CHAIN_ID * 2 + 35)) % 256
+    else:
+        parity = v
+    assert parity in (0, 1)
+    assert recover_espace_address(sig, parity, keccak_256(transaction).digest()) == address
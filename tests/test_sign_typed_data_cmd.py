@@ -0,0 +1,148 @@
+import pytest
+from sha3 import keccak_256
+
+from application_client.command_sender import ConfluxCommandSender, Errors
+from application_client.response_unpacker import unpack_get_public_key_response, unpack_vrs_response
+from ragger.error import ExceptionRAPDU
+from utils import check_rs_digest_signature_validity
+
+
+# Struct definitions, as (name, [(type, field name)]); the domain comes first
+TYPES = [
+    ("CIP23Domain", [("string", "name"), ("string", "version"),
+                     ("uint256", "chainId"), ("address", "verifyingContract")]),
+    ("Person", [("string", "name"), ("address", "wallet")]),
+    ("Mail", [("Person", "from"), ("Person", "to"), ("string", "contents")]),
+]
+
+DOMAIN = {
+    "name": b"Ether Mail",
+    "version": b"1",
+    "chainId": 1029,
+    "verifyingContract": bytes.fromhex("cccccccccccccccccccccccccccccccccccccccc"),
+}
+
+
+def struct_fields(name: str) -> list:
+    return dict(TYPES)[name]
+
+
+# Field descriptor of CIP23_STRUCT_DEF, see docs/APDU.md
+def field_def(ty: str, name: str) -> bytes:
+    if ty == "string":
+        desc = b"\x05"
+    elif ty == "address":
+        desc = b"\x03"
+    elif ty == "uint256":
+        desc = bytes([0x40 | 0x02, 32])
+    else:
+        desc = b"\x00" + len(ty).to_bytes(1, "big") + ty.encode("ascii")
+    return desc + len(name).to_bytes(1, "big") + name.encode("ascii")
+
+
+def encode_type(name: str) -> str:
+    deps = sorted(t for t, _ in struct_fields(name) if t in dict(TYPES))
+    encoded = ""
+    for struct in [name] + [d for i, d in enumerate(deps) if d not in deps[:i]]:
+        fields = ",".join(f"{ty} {field}" for ty, field in struct_fields(struct))
+        encoded += f"{struct}({fields})"
+    return encoded
+
+
+def hash_struct(name: str, value: dict) -> bytes:
+    data = keccak_256(encode_type(name).encode("ascii")).digest()
+    for ty, field in struct_fields(name):
+        v = value[field]
+        if ty == "string":
+            data += keccak_256(v).digest()
+        elif ty == "address":
+            data += v.rjust(32, b"\x00")
+        elif ty == "uint256":
+            data += v.to_bytes(32, "big")
+        else:
+            data += hash_struct(ty, v)
+    return keccak_256(data).digest()
+
+
+# Streams the values of a struct field by field, depth first
+def send_values(client: ConfluxCommandSender, name: str, value: dict) -> None:
+    for ty, field in struct_fields(name):
+        v = value[field]
+        if ty in dict(TYPES):
+            send_values(client, ty, v)
+            continue
+        if ty == "uint256":
+            v = v.to_bytes((v.bit_length() + 7) // 8, "big")
+        client.cip23_struct_impl(0xFF, len(v).to_bytes(2, "big") + v)
+
+
+def send_typed_data(client: ConfluxCommandSender, message: dict) -> None:
+    for name, fields in TYPES:
+        client.cip23_struct_def(0x00, name.encode("ascii"))
+        for ty, field in fields:
+            client.cip23_struct_def(0xFF, field_def(ty, field))
+
+    client.cip23_struct_impl(0x00, b"CIP23Domain")
+    send_values(client, "CIP23Domain", DOMAIN)
+    client.cip23_struct_impl(0x00, b"Mail")
+    send_values(client, "Mail", message)
+
+
+def sign_mail(backend, scenario_navigator, contents: bytes) -> None:
+    client = ConfluxCommandSender(backend)
+    path: str = "m/44'/503'/0'/0/0"
+
+    rapdu = client.get_public_key(path=path)
+    _, public_key, _, _ = unpack_get_public_key_response(rapdu.data)
+
+    message = {
+        "from": {"name": b"Cow", "wallet": bytes.fromhex("cd2a3d9f938e13cd947ec05abc7fe734df8dd826")},
+        "to": {"name": b"Bob", "wallet": bytes.fromhex("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb")},
+        "contents": contents,
+    }
+    send_typed_data(client, message)
+
+    with client.sign_typed_data(path=path):
+        scenario_navigator.review_approve()
+
+    response = client.get_async_response().data
+    _, sig, _ = unpack_vrs_response(response)
+    domain_hash = hash_struct("CIP23Domain", DOMAIN)
+    message_hash = hash_struct("Mail", message)
+    digest = keccak_256(b"\x19\x01" + domain_hash + message_hash).digest()
+    assert check_rs_digest_signature_validity(public_key, sig, digest)
+
+
+# In this test a typed message with nested structs is streamed, reviewed in
+# full and signed. Nested members are displayed with their path, e.g.
+# `to.wallet`.
+def test_sign_typed_data_full(backend, scenario_navigator):
+    sign_mail(backend, scenario_navigator, b"Hello, Bob!")
+
+
+# In this test a string that is not printable ASCII is displayed in hex
+def test_sign_typed_data_full_binary_string(backend, scenario_navigator):
+    sign_mail(backend, scenario_navigator, b"Hello\x00Bob")
+
+
+# In this test a struct with more fields than the device can hold is rejected
+def test_sign_typed_data_too_many_fields(backend):
+    client = ConfluxCommandSender(backend)
+
+    client.cip23_struct_def(0x00, b"Wide")
+    for i in range(32):
+        client.cip23_struct_def(0xFF, field_def("address", f"field{i}"))
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.cip23_struct_def(0xFF, field_def("address", "field32"))
+    assert e.value.status == Errors.SW_INVALID_DATA
+
+
+# In this test more structs than the device can hold are rejected
+def test_sign_typed_data_too_many_structs(backend):
+    client = ConfluxCommandSender(backend)
+
+    for i in range(16):
+        client.cip23_struct_def(0x00, f"Struct{i}".encode("ascii"))
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.cip23_struct_def(0x00, b"Struct16")
+    assert e.value.status == Errors.SW_INVALID_DATA
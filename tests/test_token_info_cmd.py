@@ -0,0 +1,28 @@
+import pytest
+
+from ragger.error import ExceptionRAPDU
+from application_client.command_sender import ConfluxCommandSender, Errors
+from utils import sign_token_record
+
+TOKEN = bytes.fromhex("8b8689c7f3014a4d86e4d1d0daaf74a47f5e0f27")
+
+
+# In this test we check that a token record signed by the test signer is accepted
+def test_provide_token_info(backend):
+    client = ConfluxCommandSender(backend)
+    record = sign_token_record("USDT", TOKEN, 18, 1029)
+
+    response = client.provide_token_info(record)
+    assert response.status == 0x9000
+
+
+# In this test we check that a token record altered after signing is rejected
+def test_provide_token_info_bad_signature(backend):
+    client = ConfluxCommandSender(backend)
+    record = bytearray(sign_token_record("USDT", TOKEN, 18, 1029))
+    # Change the decimals
+    record[1 + 4 + 20] = 6
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_token_info(bytes(record))
+    assert e.value.status == Errors.SW_INVALID_DATA
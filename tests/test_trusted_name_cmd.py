@@ -0,0 +1,28 @@
+import pytest
+
+from ragger.error import ExceptionRAPDU
+from application_client.command_sender import ConfluxCommandSender, Errors
+from utils import sign_trusted_name
+
+ADDRESS = bytes.fromhex("1b01a4e7b0a1d7a7e1e4a4c1c1f1e7b5a0c3d2e1")
+
+
+# In this test we check that a trusted name signed by the test signer is accepted
+def test_provide_trusted_name(backend):
+    client = ConfluxCommandSender(backend)
+    record = sign_trusted_name(ADDRESS, 1029, "alice.web3", 0)
+
+    response = client.provide_trusted_name(record)
+    assert response.status == 0x9000
+
+
+# In this test we check that a trusted name altered after signing is rejected
+def test_provide_trusted_name_bad_signature(backend):
+    client = ConfluxCommandSender(backend)
+    record = bytearray(sign_trusted_name(ADDRESS, 1029, "alice.web3", 0))
+    # Change the source
+    record[1 + 4 + 20] = 1
+
+    with pytest.raises(ExceptionRAPDU) as e:
+        client.provide_trusted_name(bytes(record))
+    assert e.value.status == Errors.SW_INVALID_DATA
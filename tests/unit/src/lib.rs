@@ -0,0 +1,24 @@
+//! Host build of the app modules that do not depend on the device SDK, so
+//! that their `#[cfg(test)]` unit tests can run with `cargo test`.
+//!
+//! The modules are included from `src/`, the types they need from modules
+//! that depend on the SDK are replaced by the ones of [`types`].
+#![no_std]
+#![allow(dead_code)]
+
+extern crate alloc;
+
+#[path = "../../../src/cfx_addr/mod.rs"]
+pub mod cfx_addr;
+#[path = "../../../src/consts.rs"]
+pub mod consts;
+#[path = "../../../src/contracts/mod.rs"]
+pub mod contracts;
+pub mod types;
+
+/// Status words returned by the included modules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppSW {
+    InvalidData,
+    AddrDisplayFail,
+}
@@ -0,0 +1,65 @@
+#[path = "../../../../src/types/descriptor.rs"]
+mod descriptor;
+#[path = "../../../../src/types/primitives.rs"]
+mod primitives;
+#[path = "../../../../src/types/record.rs"]
+mod record;
+#[path = "../../../../src/types/token.rs"]
+mod token;
+#[path = "../../../../src/types/trusted_name.rs"]
+mod trusted_name;
+
+pub use descriptor::{
+    CallDescriptor, DescriptorCache, DescriptorRecord, ParamFormat, ParamType, TokenRef,
+};
+pub use primitives::{Address, H256, U256};
+pub use token::{TokenCache, TokenInfo, TokenRecord};
+pub use trusted_name::{NameCache, NameRecord, NameSource, TrustedName};
+
+use crate::consts::ADDRRESS_BYTES_LEN;
+use alloc::{string::String, vec::Vec};
+
+// The following types mirror the ones of `src/types`, without what depends on
+// the device SDK.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisplayValue {
+    Text(String),
+    Address([u8; ADDRRESS_BYTES_LEN]),
+    EspaceAddress([u8; ADDRRESS_BYTES_LEN]),
+}
+
+impl DisplayValue {
+    pub fn chain_address(&self, espace: bool) -> Option<&[u8; ADDRRESS_BYTES_LEN]> {
+        match self {
+            DisplayValue::Address(addr) => Some(addr),
+            DisplayValue::EspaceAddress(addr) if espace => Some(addr),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayField {
+    pub name: String,
+    pub value: DisplayValue,
+}
+
+#[derive(Default)]
+pub struct Transaction {
+    pub to: Option<Address>,
+    pub data: Vec<u8>,
+    pub value: U256,
+    pub chain_id: u64,
+    pub espace: bool,
+}
+
+impl Transaction {
+    pub fn data_truncated(&self) -> bool {
+        false
+    }
+
+    pub fn is_espace(&self) -> bool {
+        self.espace
+    }
+}
@@ -1,16 +1,21 @@
 from pathlib import Path
 from hashlib import sha256
 from sha3 import keccak_256
+from eth_keys import keys
 
 from ecdsa.curves import SECP256k1
-from ecdsa.keys import VerifyingKey
-from ecdsa.util import sigdecode_der, sigdecode_string
+from ecdsa.keys import SigningKey, VerifyingKey
+from ecdsa.util import sigdecode_der, sigdecode_string, sigencode_der
 
 
 ROOT_SCREENSHOT_PATH = Path(__file__).parent.resolve()
 
 PREFIX = b"\x19Conflux Signed Message:\n"
 
+# Private key of the test token signer, whose public key is built into the app
+# with the test-signer feature when TOKEN_SIGNER_PUBKEY is not set
+TOKEN_SIGNER_PRIVATE_KEY = bytes.fromhex("adcfe7eedf25a311758022a6f469023584cae839ea2d9c885c9ccc4249220aa5")
+
 # Check if a des signature of a given message is valid
 def check_signature_validity(public_key: bytes, signature: bytes, message: bytes) -> bool:
     pk: VerifyingKey = VerifyingKey.from_string(
@@ -35,6 +40,19 @@ def check_rs_signature_validity(public_key: bytes, signature: bytes, message: by
                      sigdecode=sigdecode_string)
 
 
+# Check if a signature of a given 32-byte hash is valid
+# signature is r+s (not der encoded)
+def check_rs_digest_signature_validity(public_key: bytes, signature: bytes, digest: bytes) -> bool:
+    pk: VerifyingKey = VerifyingKey.from_string(
+        public_key,
+        curve=SECP256k1,
+        hashfunc=sha256
+    )
+    return pk.verify_digest(signature=signature,
+                            digest=digest,
+                            sigdecode=sigdecode_string)
+
+
 # Check if a signature of a given message is valid
 # signature is r+s (not der encoded)
 def check_rs_prefix_msg_signature_validity(public_key: bytes, signature: bytes, message: bytes) -> bool:
@@ -48,3 +66,45 @@ def check_rs_prefix_msg_signature_validity(public_key: bytes, signature: bytes,
                      data=message,
                      hashfunc=keccak_256,
                      sigdecode=sigdecode_string)
+
+
+# Build a PROVIDE_TOKEN_INFO record signed by the test token signer
+def sign_token_record(ticker: str, address: bytes, decimals: int, chain_id: int) -> bytes:
+    record = (len(ticker).to_bytes(1, "big") + ticker.encode("ascii") + address
+              + decimals.to_bytes(1, "big") + chain_id.to_bytes(4, "big"))
+    return sign_record(record)
+
+
+# Build a PROVIDE_CALL_DESCRIPTOR record signed by the test token signer, each
+# parameter being a (type, format, token, name) tuple, the token being None
+# for other formats than token amounts
+def sign_call_descriptor(address: bytes, chain_id: int, selector: bytes, operation: str,
+                         params: list) -> bytes:
+    record = (b"\x00" + chain_id.to_bytes(4, "big") + address + selector
+              + len(operation).to_bytes(1, "big") + operation.encode("ascii")
+              + len(params).to_bytes(1, "big"))
+    for (kind, fmt, token, name) in params:
+        record += bytes([kind, fmt]) + (bytes([token]) if token is not None else b"")
+        record += len(name).to_bytes(1, "big") + name.encode("ascii")
+    return sign_record(record)
+
+
+# Build a PROVIDE_TRUSTED_NAME record signed by the test token signer, the
+# source being 0 for CNS names and 1 for curated names
+def sign_trusted_name(address: bytes, chain_id: int, name: str, source: int) -> bytes:
+    record = (b"\x80" + chain_id.to_bytes(4, "big") + address + source.to_bytes(1, "big")
+              + len(name).to_bytes(1, "big") + name.encode("ascii"))
+    return sign_record(record)
+
+
+# Append the signature of the test token signer to a record
+def sign_record(record: bytes) -> bytes:
+    sk: SigningKey = SigningKey.from_string(TOKEN_SIGNER_PRIVATE_KEY, curve=SECP256k1)
+    return record + sk.sign_deterministic(record, hashfunc=sha256, sigencode=sigencode_der)
+
+
+# Recover the eSpace address that signed a given 32-byte hash, from a r+s
+# signature and its y parity
+def recover_espace_address(signature: bytes, parity: int, digest: bytes) -> bytes:
+    sig = keys.Signature(signature_bytes=signature + bytes([parity]))
+    return sig.recover_public_key_from_msg_hash(digest).to_canonical_address()